@@ -44,6 +44,11 @@ impl CastleRights {
         self.rooks[index]
     }
 
+    #[inline]
+    pub fn king_square(&self, index: usize) -> Square {
+        self.kings[index]
+    }
+
     #[inline]
     pub fn get_castle_mask(&self) -> [u8; 64] {
         let mut result = [0u8; 64];
@@ -26,6 +26,11 @@ impl ZobristKey {
     pub(crate) fn add_en_passant(&mut self, square: Square) {
         self.0 ^= SEEDS[785 + usize::from(square) % 8]
     }
+
+    #[inline]
+    pub(crate) fn add_repetitions(&mut self, repetitions: u64) {
+        self.0 ^= REPETITION_SEED.wrapping_mul(repetitions)
+    }
 }
 
 impl From<ZobristKey> for u64 {
@@ -41,6 +46,8 @@ impl Display for ZobristKey {
     }
 }
 
+const REPETITION_SEED: u64 = 0x9E3779B97F4A7C15;
+
 const SEEDS: [u64; 793] = [
     6010607256382380006,
     386869187810051925,
@@ -1,4 +1,4 @@
-use crate::{base_structures::CastleRights, board::ChessBoard, Piece, Side, Square, FEN};
+use crate::{base_structures::CastleRights, board::ChessBoard, PackedBoard, Piece, Side, Square, FEN};
 
 impl From<&FEN> for ChessBoard {
     fn from(value: &FEN) -> Self {
@@ -80,6 +80,22 @@ impl From<&FEN> for ChessBoard {
     }
 }
 
+impl From<&PackedBoard> for ChessBoard {
+    fn from(value: &PackedBoard) -> Self {
+        let mut board = Self::default();
+
+        value.map_pieces(|square, piece, side| board.set_piece_on_square(square, piece, side));
+
+        board.side = value.side();
+        board.castle_rights = CastleRights::create_base(value.rook_squares(), value.king_squares());
+        board.castle_rights.set_rights(value.castle_rights_value());
+        board.en_passant_square = value.en_passant_square();
+        board.half_moves = value.half_moves();
+
+        board
+    }
+}
+
 impl From<&ChessBoard> for FEN {
     fn from(value: &ChessBoard) -> Self {
         let mut fen = String::new();
@@ -1,6 +1,6 @@
 use crate::{
     board::{chess_board::ChessBoard, move_history::MoveHistory},
-    Move,
+    Move, ZobristKey,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -20,6 +20,14 @@ impl ChessPosition {
         &self.history
     }
 
+    //Board hash that also tells how many times the position already occurred since the last zeroing move
+    #[inline]
+    pub fn repetition_key(&self) -> ZobristKey {
+        let mut key = self.board.hash();
+        key.add_repetitions((self.history.get_repetitions(key) - 1).max(0) as u64);
+        key
+    }
+
     #[inline]
     pub fn reset_history(&mut self) {
         self.history.reset()
@@ -0,0 +1,116 @@
+use std::io::{Read, Write};
+
+use crate::{ChessBoard, Move, PackedBoard};
+
+//Game record
+//PackedBoard of the starting position, holding the game result
+//(u16 move, i16 score) per played move, score is white relative and belongs to the position before the move
+//Move::NULL with a zero score terminates the game
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameData {
+    start_position: PackedBoard,
+    moves: Vec<(Move, i16)>,
+}
+
+impl GameData {
+    pub fn new(board: &ChessBoard) -> Self {
+        Self {
+            start_position: PackedBoard::from(board),
+            moves: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    #[inline]
+    pub fn start_position(&self) -> ChessBoard {
+        ChessBoard::from(&self.start_position)
+    }
+
+    #[inline]
+    pub fn moves(&self) -> &[(Move, i16)] {
+        &self.moves
+    }
+
+    #[inline]
+    pub fn result(&self) -> u8 {
+        self.start_position.result()
+    }
+
+    #[inline]
+    pub fn set_result(&mut self, result: u8) {
+        self.start_position.set_result(result)
+    }
+
+    #[inline]
+    pub fn push(&mut self, mv: Move, score: i16) {
+        self.moves.push((mv, score))
+    }
+
+    pub fn map_positions<F: FnMut(&ChessBoard, Move, i16)>(&self, mut method: F) {
+        let mut board = self.start_position();
+        let mask = board.castle_rights().get_castle_mask();
+
+        for &(mv, score) in &self.moves {
+            method(&board, mv, score);
+            board.make_move(mv, &mask);
+        }
+    }
+
+    pub fn map_packed_positions<F: FnMut(PackedBoard)>(&self, mut method: F) {
+        let result = self.result();
+        self.map_positions(|board, _, score| method(PackedBoard::from_board(board, score, result)));
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.start_position.to_bytes())?;
+
+        for &(mv, score) in &self.moves {
+            writer.write_all(&u16::from(mv).to_le_bytes())?;
+            writer.write_all(&score.to_le_bytes())?;
+        }
+
+        writer.write_all(&[0u8; 4])
+    }
+
+    //Returns None once the reader is exhausted before the start of a new game.
+    pub fn read_from<R: Read>(reader: &mut R) -> std::io::Result<Option<Self>> {
+        let mut start_position = [0u8; PackedBoard::SIZE];
+
+        match reader.read_exact(&mut start_position) {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let mut result = Self {
+            start_position: PackedBoard::from_bytes(&start_position),
+            moves: Vec::new(),
+        };
+
+        loop {
+            let mut entry = [0u8; 4];
+            reader.read_exact(&mut entry)?;
+
+            let mv = Move::from(u16::from_le_bytes([entry[0], entry[1]]));
+            let score = i16::from_le_bytes([entry[2], entry[3]]);
+
+            if mv == Move::NULL {
+                break;
+            }
+
+            result.moves.push((mv, score));
+        }
+
+        Ok(Some(result))
+    }
+}
@@ -0,0 +1,7 @@
+mod game_data;
+mod packed_board;
+mod policy_data;
+
+pub use game_data::GameData;
+pub use packed_board::PackedBoard;
+pub use policy_data::PolicyData;
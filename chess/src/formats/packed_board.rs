@@ -0,0 +1,160 @@
+use crate::{Bitboard, ChessBoard, Piece, Side, Square};
+
+//36 byte position record
+//0..8   -> occupancy bitboard
+//8..24  -> 4 bit piece per occupied square in ls1b order (bits 0..2 piece, bit 3 side)
+//24     -> castle rights value (bits 0..3), side to move (bit 7)
+//25     -> en passant square
+//26     -> half move clock
+//27..31 -> castle rook squares
+//31..33 -> castle king squares
+//33..35 -> score, white relative
+//35     -> game result, white relative
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PackedBoard {
+    occupancy: u64,
+    pieces: [u8; 16],
+    flags: u8,
+    en_passant_square: u8,
+    half_moves: u8,
+    rooks: [u8; 4],
+    kings: [u8; 2],
+    score: i16,
+    result: u8,
+}
+
+impl PackedBoard {
+    pub const SIZE: usize = 36;
+
+    pub const BLACK_WIN: u8 = 0;
+    pub const DRAW: u8 = 1;
+    pub const WHITE_WIN: u8 = 2;
+
+    pub fn from_board(board: &ChessBoard, score: i16, result: u8) -> Self {
+        let mut pieces = [0u8; 16];
+        let mut idx = 0;
+
+        board.occupancy().map(|square| {
+            let piece = u8::from(board.piece_on_square(square)) | (u8::from(board.color_on_square(square)) << 3);
+            pieces[idx / 2] |= piece << (4 * (idx % 2));
+            idx += 1;
+        });
+
+        let castle_rights = board.castle_rights();
+
+        Self {
+            occupancy: board.occupancy().get_value(),
+            pieces,
+            flags: u8::from(castle_rights) | (u8::from(board.side()) << 7),
+            en_passant_square: u8::from(board.en_passant_square()),
+            half_moves: board.half_moves(),
+            rooks: [0, 1, 2, 3].map(|idx| u8::from(castle_rights.rook_square(idx))),
+            kings: [0, 1].map(|idx| u8::from(castle_rights.king_square(idx))),
+            score,
+            result,
+        }
+    }
+
+    #[inline]
+    pub fn score(&self) -> i16 {
+        self.score
+    }
+
+    #[inline]
+    pub fn result(&self) -> u8 {
+        self.result
+    }
+
+    #[inline]
+    pub fn set_score(&mut self, score: i16) {
+        self.score = score
+    }
+
+    #[inline]
+    pub fn set_result(&mut self, result: u8) {
+        self.result = result
+    }
+
+    #[inline]
+    pub fn side(&self) -> Side {
+        Side::from(self.flags >> 7)
+    }
+
+    #[inline]
+    pub(crate) fn castle_rights_value(&self) -> u8 {
+        self.flags & 0b1111
+    }
+
+    #[inline]
+    pub(crate) fn en_passant_square(&self) -> Square {
+        Square::from(self.en_passant_square)
+    }
+
+    #[inline]
+    pub(crate) fn half_moves(&self) -> u8 {
+        self.half_moves
+    }
+
+    #[inline]
+    pub(crate) fn rook_squares(&self) -> [Square; 4] {
+        self.rooks.map(Square::from)
+    }
+
+    #[inline]
+    pub(crate) fn king_squares(&self) -> [Square; 2] {
+        self.kings.map(Square::from)
+    }
+
+    pub(crate) fn map_pieces<F: FnMut(Square, Piece, Side)>(&self, mut method: F) {
+        let mut idx = 0;
+
+        Bitboard::from(self.occupancy).map(|square| {
+            let piece = (self.pieces[idx / 2] >> (4 * (idx % 2))) & 0b1111;
+            method(square, Piece::from(piece & 0b111), Side::from(piece >> 3));
+            idx += 1;
+        });
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut result = [0u8; Self::SIZE];
+
+        result[0..8].copy_from_slice(&self.occupancy.to_le_bytes());
+        result[8..24].copy_from_slice(&self.pieces);
+        result[24] = self.flags;
+        result[25] = self.en_passant_square;
+        result[26] = self.half_moves;
+        result[27..31].copy_from_slice(&self.rooks);
+        result[31..33].copy_from_slice(&self.kings);
+        result[33..35].copy_from_slice(&self.score.to_le_bytes());
+        result[35] = self.result;
+
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+        let mut occupancy = [0u8; 8];
+        occupancy.copy_from_slice(&bytes[0..8]);
+
+        let mut pieces = [0u8; 16];
+        pieces.copy_from_slice(&bytes[8..24]);
+
+        Self {
+            occupancy: u64::from_le_bytes(occupancy),
+            pieces,
+            flags: bytes[24],
+            en_passant_square: bytes[25],
+            half_moves: bytes[26],
+            rooks: [bytes[27], bytes[28], bytes[29], bytes[30]],
+            kings: [bytes[31], bytes[32]],
+            score: i16::from_le_bytes([bytes[33], bytes[34]]),
+            result: bytes[35],
+        }
+    }
+}
+
+impl From<&ChessBoard> for PackedBoard {
+    fn from(value: &ChessBoard) -> Self {
+        Self::from_board(value, 0, Self::DRAW)
+    }
+}
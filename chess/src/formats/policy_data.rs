@@ -0,0 +1,80 @@
+use std::io::{Read, Write};
+
+use crate::{ChessBoard, Move, PackedBoard};
+
+//Policy record
+//PackedBoard of the position
+//u8 move count, then (u16 move, u16 visits) per root child
+//Visits are rescaled so that the most visited child always has u16::MAX
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PolicyData {
+    position: PackedBoard,
+    visits: Vec<(Move, u16)>,
+}
+
+impl PolicyData {
+    pub fn new(board: &ChessBoard, visits: &[(Move, u32)]) -> Self {
+        let max_visits = visits.iter().map(|&(_, visits)| visits).max().unwrap_or(0).max(1);
+
+        Self {
+            position: PackedBoard::from(board),
+            visits: visits
+                .iter()
+                .map(|&(mv, visits)| (mv, (u64::from(visits) * u64::from(u16::MAX) / u64::from(max_visits)) as u16))
+                .collect(),
+        }
+    }
+
+    #[inline]
+    pub fn board(&self) -> ChessBoard {
+        ChessBoard::from(&self.position)
+    }
+
+    #[inline]
+    pub fn visits(&self) -> &[(Move, u16)] {
+        &self.visits
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.position.to_bytes())?;
+        writer.write_all(&[self.visits.len() as u8])?;
+
+        for &(mv, visits) in &self.visits {
+            writer.write_all(&u16::from(mv).to_le_bytes())?;
+            writer.write_all(&visits.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    //Returns None once the reader is exhausted before the start of a new record.
+    pub fn read_from<R: Read>(reader: &mut R) -> std::io::Result<Option<Self>> {
+        let mut position = [0u8; PackedBoard::SIZE];
+
+        match reader.read_exact(&mut position) {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+
+        let mut count = [0u8; 1];
+        reader.read_exact(&mut count)?;
+
+        let mut visits = Vec::with_capacity(count[0] as usize);
+        for _ in 0..count[0] {
+            let mut entry = [0u8; 4];
+            reader.read_exact(&mut entry)?;
+
+            visits.push((
+                Move::from(u16::from_le_bytes([entry[0], entry[1]])),
+                u16::from_le_bytes([entry[2], entry[3]]),
+            ));
+        }
+
+        Ok(Some(Self {
+            position: PackedBoard::from_bytes(&position),
+            visits,
+        }))
+    }
+}
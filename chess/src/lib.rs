@@ -1,6 +1,7 @@
 mod attacks;
 mod base_structures;
 mod board;
+mod formats;
 mod move_gen;
 
 use std::time::Duration;
@@ -8,6 +9,7 @@ use std::time::Instant;
 
 pub use attacks::Attacks;
 pub use base_structures::Bitboard;
+pub use base_structures::CastleRights;
 pub use base_structures::Move;
 pub use base_structures::MoveFlag;
 pub use base_structures::Piece;
@@ -17,6 +19,9 @@ pub use base_structures::FEN;
 pub use base_structures::ZobristKey;
 pub use board::ChessBoard;
 pub use board::ChessPosition;
+pub use formats::GameData;
+pub use formats::PackedBoard;
+pub use formats::PolicyData;
 
 pub const DEFAULT_PERFT_DEPTH: u8 = 5;
 
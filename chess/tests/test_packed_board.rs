@@ -0,0 +1,129 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use chess::{ChessBoard, GameData, Move, PackedBoard, PolicyData, FEN};
+
+fn round_trip(board: &ChessBoard) {
+    let packed = PackedBoard::from_board(board, -137, PackedBoard::WHITE_WIN);
+    let unpacked = PackedBoard::from_bytes(&packed.to_bytes());
+
+    assert_eq!(packed, unpacked);
+    assert_eq!(unpacked.score(), -137);
+    assert_eq!(unpacked.result(), PackedBoard::WHITE_WIN);
+    assert_eq!(ChessBoard::from(&unpacked), *board);
+    assert_eq!(ChessBoard::from(&unpacked).hash(), board.hash());
+}
+
+#[test]
+fn standard_round_trip() {
+    let file = File::open("./tests/standard.epd").unwrap();
+    let reader = BufReader::new(file);
+
+    for line in reader.lines() {
+        let line = line.unwrap();
+        let fen = FEN::from(line.split(';').next().unwrap());
+        let board = ChessBoard::from(&fen);
+
+        round_trip(&board);
+
+        board.map_legal_moves(|mv| {
+            let mut board_copy = board;
+            board_copy.make_move_no_mask(mv);
+            round_trip(&board_copy);
+        });
+    }
+}
+
+#[test]
+fn frc_round_trip() {
+    let file = File::open("./tests/fischer.epd").unwrap();
+    let reader = BufReader::new(file);
+
+    for line in reader.lines() {
+        let line = line.unwrap();
+        let fen = FEN::from(line.split(';').next().unwrap());
+        let board = ChessBoard::from(&fen);
+
+        round_trip(&board);
+        assert_eq!(FEN::from(&ChessBoard::from(&PackedBoard::from(&board))), FEN::from(&board));
+    }
+}
+
+#[test]
+fn en_passant_and_half_moves() {
+    let board = ChessBoard::from(&FEN::from("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3"));
+    round_trip(&board);
+
+    let board = ChessBoard::from(&FEN::from("8/8/1p2k1p1/3p3p/1p1P1P1P/1P2PK2/8/8 w - - 93 54"));
+    let unpacked = ChessBoard::from(&PackedBoard::from(&board));
+    assert_eq!(unpacked.half_moves(), 93);
+    assert_eq!(unpacked, board);
+}
+
+#[test]
+fn game_data_round_trip() {
+    let start = ChessBoard::from(&FEN::kiwipete_position());
+
+    let mut game = GameData::new(&start);
+    let mut board = start;
+
+    for ply in 0..12 {
+        let mut moves = Vec::new();
+        board.map_legal_moves(|mv| moves.push(mv));
+
+        let mv = moves[(ply * 7) % moves.len()];
+        game.push(mv, ply as i16 * 10 - 50);
+        board.make_move_no_mask(mv);
+    }
+
+    game.set_result(PackedBoard::BLACK_WIN);
+
+    let mut bytes = Vec::new();
+    game.write_to(&mut bytes).unwrap();
+    game.write_to(&mut bytes).unwrap();
+
+    assert_eq!(bytes.len(), 2 * (PackedBoard::SIZE + 4 * (game.len() + 1)));
+
+    let mut reader = bytes.as_slice();
+    let first = GameData::read_from(&mut reader).unwrap().unwrap();
+    let second = GameData::read_from(&mut reader).unwrap().unwrap();
+
+    assert_eq!(first, game);
+    assert_eq!(second, game);
+    assert!(GameData::read_from(&mut reader).unwrap().is_none());
+
+    let mut positions = 0;
+    game.map_packed_positions(|packed| {
+        assert_eq!(packed.result(), PackedBoard::BLACK_WIN);
+        assert_eq!(packed.score(), positions as i16 * 10 - 50);
+        positions += 1;
+    });
+
+    assert_eq!(positions, game.len());
+}
+
+#[test]
+fn policy_data_round_trip() {
+    let board = ChessBoard::from(&FEN::start_position());
+
+    let mut visits = Vec::new();
+    board.map_legal_moves(|mv| visits.push((mv, visits.len() as u32 * 3)));
+
+    let policy = PolicyData::new(&board, &visits);
+
+    let mut bytes = Vec::new();
+    policy.write_to(&mut bytes).unwrap();
+
+    let mut reader = bytes.as_slice();
+    let result = PolicyData::read_from(&mut reader).unwrap().unwrap();
+
+    assert_eq!(result, policy);
+    assert_eq!(result.board(), board);
+    assert_eq!(result.visits().len(), 20);
+    assert_eq!(result.visits()[19].1, u16::MAX);
+    assert_eq!(result.visits()[0], (visits[0].0, 0));
+    assert_ne!(result.visits()[0].0, Move::NULL);
+    assert!(PolicyData::read_from(&mut reader).unwrap().is_none());
+}
@@ -0,0 +1,86 @@
+#[derive(Debug, Clone)]
+pub struct DatagenOptions {
+    threads: usize,
+    positions: u64,
+    iters: u64,
+    random_plies: usize,
+    hash: i64,
+    output: String,
+    policy_output: Option<String>,
+}
+
+impl Default for DatagenOptions {
+    fn default() -> Self {
+        Self {
+            threads: 1,
+            positions: 1_000_000,
+            iters: 5000,
+            random_plies: 8,
+            hash: 16,
+            output: String::from("data.bin"),
+            policy_output: None,
+        }
+    }
+}
+
+impl DatagenOptions {
+    pub fn from_args(args: &[String]) -> Self {
+        let mut result = Self::default();
+
+        for (idx, arg) in args.iter().enumerate() {
+            let value = if args.len() > idx + 1 {
+                args[idx + 1].as_str()
+            } else {
+                continue;
+            };
+
+            match arg.as_str() {
+                "threads" => result.threads = value.parse::<usize>().unwrap_or(result.threads).max(1),
+                "positions" => result.positions = value.parse::<u64>().unwrap_or(result.positions),
+                "nodes" => result.iters = value.parse::<u64>().unwrap_or(result.iters).max(1),
+                "random_plies" => result.random_plies = value.parse::<usize>().unwrap_or(result.random_plies),
+                "hash" => result.hash = value.parse::<i64>().unwrap_or(result.hash).max(1),
+                "output" => result.output = value.to_string(),
+                "policy" => result.policy_output = Some(value.to_string()),
+                _ => continue,
+            }
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    #[inline]
+    pub fn positions(&self) -> u64 {
+        self.positions
+    }
+
+    #[inline]
+    pub fn iters(&self) -> u64 {
+        self.iters
+    }
+
+    #[inline]
+    pub fn random_plies(&self) -> usize {
+        self.random_plies
+    }
+
+    #[inline]
+    pub fn hash(&self) -> i64 {
+        self.hash
+    }
+
+    #[inline]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    #[inline]
+    pub fn policy_output(&self) -> Option<&str> {
+        self.policy_output.as_deref()
+    }
+}
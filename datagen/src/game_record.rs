@@ -0,0 +1,72 @@
+use std::io::Write;
+
+use chess::{ChessBoard, GameData, Move, PackedBoard, PolicyData, Side};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameOutcome {
+    WhiteWin,
+    Draw,
+    BlackWin,
+}
+
+impl GameOutcome {
+    pub fn win_for(side: Side) -> Self {
+        if side == Side::WHITE {
+            Self::WhiteWin
+        } else {
+            Self::BlackWin
+        }
+    }
+
+    pub fn as_result(&self) -> u8 {
+        match self {
+            Self::WhiteWin => PackedBoard::WHITE_WIN,
+            Self::Draw => PackedBoard::DRAW,
+            Self::BlackWin => PackedBoard::BLACK_WIN,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    game: GameData,
+    policy: Vec<PolicyData>,
+}
+
+impl GameRecord {
+    pub fn new(start_position: &ChessBoard) -> Self {
+        Self {
+            game: GameData::new(start_position),
+            policy: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.game.len()
+    }
+
+    //Score is expected to be white relative, same as the outcome.
+    #[inline]
+    pub fn push(&mut self, board: &ChessBoard, mv: Move, score: i16, visits: &[(Move, u32)]) {
+        self.game.push(mv, score);
+        self.policy.push(PolicyData::new(board, visits));
+    }
+
+    #[inline]
+    pub fn set_outcome(&mut self, outcome: GameOutcome) {
+        self.game.set_result(outcome.as_result())
+    }
+
+    pub fn write_game<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.game.write_to(writer)
+    }
+
+    pub fn write_policy<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        for policy in &self.policy {
+            policy.write_to(writer)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1 +1,128 @@
-fn main() {}
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use utils::{number_to_string, time_to_string};
+
+use crate::{datagen_options::DatagenOptions, selfplay::SelfPlay};
+
+mod datagen_options;
+mod game_record;
+mod selfplay;
+
+fn main() {
+    let args = std::env::args().collect::<Vec<String>>();
+    let options = DatagenOptions::from_args(&args[1..]);
+
+    println!("Threads:      {}", options.threads());
+    println!("Positions:    {}", number_to_string(options.positions() as u128));
+    println!("Nodes:        {}", number_to_string(options.iters() as u128));
+    println!("Random plies: {}", options.random_plies());
+    println!("Output:       {}", options.output());
+
+    if let Some(policy_output) = options.policy_output() {
+        println!("Policy:       {policy_output}");
+    }
+
+    let file = match File::create(options.output()) {
+        Ok(file) => file,
+        Err(err) => {
+            println!("Failed to create '{}': {err}", options.output());
+            return;
+        }
+    };
+
+    let writer = Mutex::new(BufWriter::new(file));
+
+    let policy_writer = if let Some(policy_output) = options.policy_output() {
+        match File::create(policy_output) {
+            Ok(file) => Some(Mutex::new(BufWriter::new(file))),
+            Err(err) => {
+                println!("Failed to create '{policy_output}': {err}");
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let positions = AtomicU64::new(0);
+    let games = AtomicU64::new(0);
+    let stop_token = AtomicBool::new(false);
+
+    let timer = Instant::now();
+
+    std::thread::scope(|s| {
+        for thread_idx in 0..options.threads() {
+            let (options, writer, policy_writer, positions, games, stop_token) =
+                (&options, &writer, &policy_writer, &positions, &games, &stop_token);
+
+            s.spawn(move || {
+                let mut selfplay = SelfPlay::new(options, thread_idx);
+
+                while !stop_token.load(Ordering::Relaxed) {
+                    let record = if let Some(record) = selfplay.play_game() {
+                        record
+                    } else {
+                        continue;
+                    };
+
+                    if record.write_game(&mut *writer.lock().unwrap()).is_err() {
+                        println!("Failed to write to '{}'.", options.output());
+                        stop_token.store(true, Ordering::Relaxed);
+                        break;
+                    }
+
+                    if let Some(policy_writer) = policy_writer {
+                        if record.write_policy(&mut *policy_writer.lock().unwrap()).is_err() {
+                            println!("Failed to write policy data.");
+                            stop_token.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+
+                    positions.fetch_add(record.len() as u64, Ordering::Relaxed);
+                    games.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+
+        while !stop_token.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_secs(1));
+
+            let positions = positions.load(Ordering::Relaxed);
+            let games = games.load(Ordering::Relaxed);
+            let time = timer.elapsed().as_millis();
+
+            println!(
+                "Positions: {} | Games: {} | Time: {} | {} pos/s",
+                number_to_string(positions as u128),
+                number_to_string(games as u128),
+                time_to_string(time),
+                number_to_string(positions as u128 * 1000 / time.max(1))
+            );
+
+            if positions >= options.positions() {
+                stop_token.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+
+    if writer.lock().unwrap().flush().is_err() {
+        println!("Failed to flush '{}'.", options.output());
+    }
+
+    if let Some(policy_writer) = &policy_writer {
+        if policy_writer.lock().unwrap().flush().is_err() {
+            println!("Failed to flush policy data.");
+        }
+    }
+
+    println!("Finished generating {} positions.", number_to_string(positions.load(Ordering::Relaxed) as u128));
+}
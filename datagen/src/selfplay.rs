@@ -0,0 +1,143 @@
+use chess::{ChessBoard, ChessPosition, Move, Side, FEN};
+use engine::{GameState, NoReport, SearchEngine, SearchLimits};
+use utils::Random;
+
+use crate::{
+    datagen_options::DatagenOptions,
+    game_record::{GameOutcome, GameRecord},
+};
+
+pub struct SelfPlay {
+    search_engine: SearchEngine,
+    search_limits: SearchLimits,
+    random: Random,
+    random_plies: usize,
+}
+
+impl SelfPlay {
+    pub fn new(options: &DatagenOptions, thread_idx: usize) -> Self {
+        let mut search_engine = SearchEngine::new();
+
+        //Contempt and draw score would bias the recorded scores, so datagen runs the search neutral.
+        let _ = search_engine.set_option("Contempt", "0");
+        let _ = search_engine.set_option("DrawScore", "50");
+        let _ = search_engine.set_option("Hash", options.hash().to_string().as_str());
+
+        search_engine.resize_tree();
+        search_engine.reinit_contempt();
+
+        let mut search_limits = SearchLimits::default();
+        search_limits.set_iters(Some(options.iters()));
+
+        Self {
+            search_engine,
+            search_limits,
+            random: Random::new(thread_idx as u64),
+            random_plies: options.random_plies(),
+        }
+    }
+
+    pub fn play_game(&mut self) -> Option<GameRecord> {
+        let mut position = self.random_opening()?;
+        let mut record = GameRecord::new(position.board());
+        let mut game_ply = self.random_plies as u16;
+
+        self.search_engine.tree().clear();
+        self.search_engine.set_position(&position, game_ply);
+
+        let draw_score = self.search_engine.options().draw_score() as f64 / 100.0;
+
+        let outcome = loop {
+            if let Some(outcome) = game_outcome(&position) {
+                break outcome;
+            }
+
+            self.search_engine.search(&self.search_limits, &mut NoReport).ok()?;
+
+            let tree = self.search_engine.tree();
+            let best_child_idx = tree.select_best_child(tree.root_index(), draw_score)?;
+            let best_child = &tree[best_child_idx];
+
+            let side = position.board().side();
+            let score = best_child.score().cp().clamp(-i16::MAX as i32, i16::MAX as i32) as i16;
+            let white_score = if side == Side::WHITE { score } else { -score };
+
+            let mv = best_child.mv();
+
+            let mut visits = Vec::with_capacity(tree.root_node().children_count());
+            tree.root_node().map_children(|child_idx| visits.push((tree[child_idx].mv(), tree[child_idx].visits())));
+
+            record.push(position.board(), mv, white_score, &visits);
+
+            //Child states are stored from the child's perspective, so a lost child is a won root.
+            match best_child.state() {
+                GameState::Loss(_) => break GameOutcome::win_for(side),
+                GameState::Win(_) => break GameOutcome::win_for(side.flipped()),
+                _ => (),
+            }
+
+            let mut new_position = position;
+            new_position.make_move_no_mask(mv);
+            game_ply += 1;
+
+            self.search_engine.tree().try_reuse(self.search_engine.root_position(), &new_position, 1, self.search_engine.options());
+            self.search_engine.set_position(&new_position, game_ply);
+
+            position = new_position;
+        };
+
+        record.set_outcome(outcome);
+
+        Some(record)
+    }
+
+    fn random_opening(&mut self) -> Option<ChessPosition> {
+        let mut position = ChessPosition::from(ChessBoard::from(&FEN::start_position()));
+
+        for _ in 0..self.random_plies {
+            let moves = legal_moves(position.board());
+
+            if moves.is_empty() {
+                return None;
+            }
+
+            position.make_move_no_mask(moves[self.random.range(moves.len())]);
+        }
+
+        if game_outcome(&position).is_some() {
+            return None;
+        }
+
+        Some(position)
+    }
+}
+
+fn legal_moves(board: &ChessBoard) -> Vec<Move> {
+    let mut moves = Vec::new();
+    board.map_legal_moves(|mv| moves.push(mv));
+    moves
+}
+
+fn game_outcome(position: &ChessPosition) -> Option<GameOutcome> {
+    let board = position.board();
+
+    let mut possible_moves = 0;
+    board.map_legal_moves(|_| possible_moves += 1);
+
+    if possible_moves == 0 {
+        return if board.is_in_check() {
+            Some(GameOutcome::win_for(board.side().flipped()))
+        } else {
+            Some(GameOutcome::Draw)
+        };
+    }
+
+    if board.half_moves() >= 100
+        || board.is_insufficient_material()
+        || position.history().get_repetitions(board.hash()) >= 3
+    {
+        return Some(GameOutcome::Draw);
+    }
+
+    None
+}
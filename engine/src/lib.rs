@@ -1,10 +1,16 @@
 mod search_engine;
 mod search_report_trait;
 mod networks;
+mod syzygy;
 
 pub use search_engine::SearchEngine;
 pub use search_engine::SearchLimits;
+pub use search_engine::GoCommand;
 pub use search_engine::SearchStats;
+pub use search_engine::SearchHandle;
+pub use search_engine::SearchResult;
+pub use search_engine::SearchLine;
+pub use search_engine::LineScore;
 pub use search_engine::Tree;
 pub use search_engine::Node;
 pub use search_engine::GameState;
@@ -14,5 +20,23 @@ pub use search_engine::PvLine;
 pub use search_engine::NodeIndex;
 pub use search_report_trait::SearchReport;
 pub use search_report_trait::NoReport;
+pub use search_report_trait::SearchEvent;
 pub use networks::ValueNetwork;
-pub use networks::PolicyNetwork;
\ No newline at end of file
+pub use networks::PolicyNetwork;
+pub use networks::value_network;
+pub use networks::policy_network;
+pub use networks::load_value_network;
+pub use networks::load_policy_network;
+pub use networks::quantise_policy_file;
+pub use networks::PolicyCache;
+pub use networks::AccumulatorStack;
+pub use networks::Standard768;
+pub use networks::Threats3072;
+pub use networks::NetworkHeader;
+pub use networks::simd;
+pub use syzygy::load_syzygy;
+pub use syzygy::syzygy_max_pieces;
+pub use syzygy::probe_wdl;
+pub use syzygy::probe_dtz;
+pub use syzygy::probe_root;
+pub use syzygy::TablebaseWDL;
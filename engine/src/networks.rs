@@ -1,7 +1,18 @@
 mod value_network;
 mod policy_network;
+mod quantised_policy_network;
 mod layers;
 mod inputs;
+mod network_header;
+mod network_loader;
+
+pub use inputs::{Standard768, Threats3072};
+pub use network_header::NetworkHeader;
+pub use layers::simd;
+pub use quantised_policy_network::PolicyCache;
+pub use value_network::AccumulatorStack;
+pub use network_loader::{load_policy_network, load_value_network, policy_network, quantise_policy_file, value_network};
+pub(crate) use network_loader::free_replaced_networks;
 
 use crate::networks::value_network::ValueNetwork;
 use crate::networks::policy_network::PolicyNetwork;
@@ -14,4 +25,4 @@ pub static ValueNetwork: ValueNetwork = unsafe {
 #[allow(non_upper_case_globals)]
 pub static PolicyNetwork: PolicyNetwork = unsafe {
     std::mem::transmute(*include_bytes!("../../resources/networks/p300cos32x32see005.network"))
-};
\ No newline at end of file
+};
@@ -1,6 +1,7 @@
 pub use accumulator::Accumulator;
 
 mod accumulator;
+pub mod simd;
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -28,6 +29,16 @@ impl<T: Copy, const INPUTS: usize, const OUTPUTS: usize> NetworkLayer<T, INPUTS,
     pub fn biases(&self) -> &Accumulator<T, OUTPUTS> {
         &self.biases
     }
+
+    #[inline]
+    pub fn weights_mut(&mut self) -> &mut [Accumulator<T, OUTPUTS>; INPUTS] {
+        &mut self.weights
+    }
+
+    #[inline]
+    pub fn biases_mut(&mut self) -> &mut Accumulator<T, OUTPUTS> {
+        &mut self.biases
+    }
 }
 
 #[repr(C)]
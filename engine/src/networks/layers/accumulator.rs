@@ -1,4 +1,4 @@
-use std::ops::{AddAssign, Mul};
+use super::simd;
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
@@ -12,13 +12,7 @@ impl<T: Copy + Default, const SIZE: usize> Default for Accumulator<T, SIZE> {
     }
 }
 
-impl<T: AddAssign<T> + Copy + Mul<T, Output = T>, const HIDDEN: usize> Accumulator<T, HIDDEN> {
-    pub fn madd(&mut self, mul: T, other: &Self) {
-        for (i, &j) in self.vals.iter_mut().zip(other.vals.iter()) {
-            *i += mul * j;
-        }
-    }
-
+impl<T: Copy, const HIDDEN: usize> Accumulator<T, HIDDEN> {
     #[inline]
     pub fn values(&self) -> &[T; HIDDEN] {
         &self.vals
@@ -28,4 +22,52 @@ impl<T: AddAssign<T> + Copy + Mul<T, Output = T>, const HIDDEN: usize> Accumulat
     pub fn values_mut(&mut self) -> &mut [T; HIDDEN] {
         &mut self.vals
     }
-}
\ No newline at end of file
+}
+//Element types with vectorised add/sub kernels.
+pub trait SimdElement: Copy {
+    fn add(acc: &mut [Self], weights: &[Self]);
+    fn sub(acc: &mut [Self], weights: &[Self]);
+}
+
+impl SimdElement for i16 {
+    #[inline]
+    fn add(acc: &mut [Self], weights: &[Self]) {
+        simd::add_i16(acc, weights)
+    }
+
+    #[inline]
+    fn sub(acc: &mut [Self], weights: &[Self]) {
+        simd::sub_i16(acc, weights)
+    }
+}
+
+impl SimdElement for f32 {
+    #[inline]
+    fn add(acc: &mut [Self], weights: &[Self]) {
+        simd::add_f32(acc, weights)
+    }
+
+    #[inline]
+    fn sub(acc: &mut [Self], weights: &[Self]) {
+        simd::sub_f32(acc, weights)
+    }
+}
+
+impl<T: SimdElement, const HIDDEN: usize> Accumulator<T, HIDDEN> {
+    #[inline]
+    pub fn add(&mut self, other: &Self) {
+        T::add(&mut self.vals, &other.vals)
+    }
+
+    #[inline]
+    pub fn sub(&mut self, other: &Self) {
+        T::sub(&mut self.vals, &other.vals)
+    }
+}
+
+impl<const HIDDEN: usize> Accumulator<f32, HIDDEN> {
+    #[inline]
+    pub fn madd(&mut self, mul: f32, other: &Self) {
+        simd::madd_f32(&mut self.vals, mul, &other.vals)
+    }
+}
@@ -0,0 +1,51 @@
+//-----------------------------------------------
+// Inference kernels, picked at compile time from the enabled target features
+// (x86-64-v2 -> sse, v3 -> avx2, v4 -> avx512). Anything else uses the scalar reference.
+// i16 slices have to be a multiple of 32 long and f32 slices a multiple of 8.
+//-----------------------------------------------
+
+pub mod scalar;
+
+//Every x86-64 kernel set is always built, its functions enable their own target features. Calling one
+//on a CPU without them is undefined, so outside of the selection below check is_x86_feature_detected first.
+#[cfg(target_arch = "x86_64")]
+pub mod sse;
+
+#[cfg(target_arch = "x86_64")]
+pub mod avx2;
+
+#[cfg(target_arch = "x86_64")]
+pub mod avx512;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "avx512bw"))]
+use avx512 as selected;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2", not(target_feature = "avx512bw")))]
+use avx2 as selected;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "sse4.1", not(target_feature = "avx2")))]
+use sse as selected;
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "sse4.1")))]
+use scalar as selected;
+
+//The selected set only needs features the whole build is compiled with, so calling it is always sound.
+macro_rules! selected_kernels {
+    ($($name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {$(
+        #[inline]
+        #[allow(unused_unsafe)]
+        pub fn $name($($arg: $ty),*) $(-> $ret)? {
+            unsafe { selected::$name($($arg),*) }
+        }
+    )*};
+}
+
+selected_kernels! {
+    add_i16(acc: &mut [i16], weights: &[i16]);
+    sub_i16(acc: &mut [i16], weights: &[i16]);
+    add_f32(acc: &mut [f32], weights: &[f32]);
+    sub_f32(acc: &mut [f32], weights: &[f32]);
+    madd_f32(acc: &mut [f32], mul: f32, weights: &[f32]);
+    screlu_dot(inputs: &[i16], weights: &[i16], max: i16) -> i32;
+    relu_dot(a: &[f32], b: &[f32]) -> f32;
+}
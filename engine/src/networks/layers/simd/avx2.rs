@@ -0,0 +1,131 @@
+use std::arch::x86_64::*;
+
+//x86-64-v3 kernels.
+
+/// # Safety
+/// The CPU has to support AVX2. `acc` and `weights` have to be equally long, a multiple of 16 values.
+#[target_feature(enable = "avx2")]
+pub fn add_i16(acc: &mut [i16], weights: &[i16]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(16));
+
+    for (value, weight) in acc.chunks_exact_mut(16).zip(weights.chunks_exact(16)) {
+        unsafe {
+            let sum = _mm256_add_epi16(load_i16(value), load_i16(weight));
+            _mm256_storeu_si256(value.as_mut_ptr() as *mut __m256i, sum);
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX2. `acc` and `weights` have to be equally long, a multiple of 16 values.
+#[target_feature(enable = "avx2")]
+pub fn sub_i16(acc: &mut [i16], weights: &[i16]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(16));
+
+    for (value, weight) in acc.chunks_exact_mut(16).zip(weights.chunks_exact(16)) {
+        unsafe {
+            let difference = _mm256_sub_epi16(load_i16(value), load_i16(weight));
+            _mm256_storeu_si256(value.as_mut_ptr() as *mut __m256i, difference);
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX2. `acc` and `weights` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "avx2")]
+pub fn add_f32(acc: &mut [f32], weights: &[f32]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(8));
+
+    for (value, weight) in acc.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+        unsafe { _mm256_storeu_ps(value.as_mut_ptr(), _mm256_add_ps(_mm256_loadu_ps(value.as_ptr()), _mm256_loadu_ps(weight.as_ptr()))) }
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX2. `acc` and `weights` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "avx2")]
+pub fn sub_f32(acc: &mut [f32], weights: &[f32]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(8));
+
+    for (value, weight) in acc.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+        unsafe { _mm256_storeu_ps(value.as_mut_ptr(), _mm256_sub_ps(_mm256_loadu_ps(value.as_ptr()), _mm256_loadu_ps(weight.as_ptr()))) }
+    }
+}
+
+//No FMA here, a fused multiply-add rounds differently from the scalar reference.
+/// # Safety
+/// The CPU has to support AVX2. `acc` and `weights` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "avx2")]
+pub fn madd_f32(acc: &mut [f32], mul: f32, weights: &[f32]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(8));
+
+    unsafe {
+        let mul = _mm256_set1_ps(mul);
+
+        for (value, weight) in acc.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+            let product = _mm256_mul_ps(mul, _mm256_loadu_ps(weight.as_ptr()));
+            _mm256_storeu_ps(value.as_mut_ptr(), _mm256_add_ps(_mm256_loadu_ps(value.as_ptr()), product));
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX2. `inputs` and `weights` have to be equally long, a multiple of 16 values.
+#[target_feature(enable = "avx2")]
+pub fn screlu_dot(inputs: &[i16], weights: &[i16], max: i16) -> i32 {
+    debug_assert!(inputs.len() == weights.len() && inputs.len().is_multiple_of(16));
+
+    unsafe {
+        let zero = _mm256_setzero_si256();
+        let max = _mm256_set1_epi16(max);
+        let mut sum = _mm256_setzero_si256();
+
+        for (input, weight) in inputs.chunks_exact(16).zip(weights.chunks_exact(16)) {
+            let activated = _mm256_min_epi16(_mm256_max_epi16(load_i16(input), zero), max);
+            let weight = load_i16(weight);
+
+            for (activated, weight) in [
+                (_mm256_castsi256_si128(activated), _mm256_castsi256_si128(weight)),
+                (_mm256_extracti128_si256(activated, 1), _mm256_extracti128_si256(weight, 1)),
+            ] {
+                let activated = _mm256_cvtepi16_epi32(activated);
+                let squared = _mm256_mullo_epi32(activated, activated);
+                sum = _mm256_add_epi32(sum, _mm256_mullo_epi32(squared, _mm256_cvtepi16_epi32(weight)));
+            }
+        }
+
+        let sum = _mm_add_epi32(_mm256_castsi256_si128(sum), _mm256_extracti128_si256(sum, 1));
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32(sum, 0b01_00_11_10));
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32(sum, 0b10_11_00_01));
+        _mm_cvtsi128_si32(sum)
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX2. `a` and `b` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "avx2")]
+pub fn relu_dot(a: &[f32], b: &[f32]) -> f32 {
+    debug_assert!(a.len() == b.len() && a.len().is_multiple_of(8));
+
+    unsafe {
+        let zero = _mm256_setzero_ps();
+        let mut sum = _mm256_setzero_ps();
+
+        for (chunk_a, chunk_b) in a.chunks_exact(8).zip(b.chunks_exact(8)) {
+            let a = _mm256_max_ps(_mm256_loadu_ps(chunk_a.as_ptr()), zero);
+            let b = _mm256_max_ps(_mm256_loadu_ps(chunk_b.as_ptr()), zero);
+            sum = _mm256_add_ps(sum, _mm256_mul_ps(a, b));
+        }
+
+        //Same reduction order as the scalar reference: lanes i and i + 4, then i and i + 2, then the last pair.
+        let quad = _mm_add_ps(_mm256_castps256_ps128(sum), _mm256_extractf128_ps(sum, 1));
+        let pair = _mm_add_ps(quad, _mm_movehl_ps(quad, quad));
+        _mm_cvtss_f32(_mm_add_ss(pair, _mm_shuffle_ps(pair, pair, 0b01)))
+    }
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load_i16(values: &[i16]) -> __m256i {
+    _mm256_loadu_si256(values.as_ptr() as *const __m256i)
+}
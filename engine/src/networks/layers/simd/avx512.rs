@@ -0,0 +1,69 @@
+use std::arch::x86_64::*;
+
+//x86-64-v4 kernels. The f32 kernels stay on AVX2, policy layers are only 32 wide
+//and the dot product has to keep the 8 lane reduction of the scalar reference.
+
+pub use super::avx2::{add_f32, madd_f32, relu_dot, sub_f32};
+
+/// # Safety
+/// The CPU has to support AVX-512BW. `acc` and `weights` have to be equally long, a multiple of 32 values.
+#[target_feature(enable = "avx512bw")]
+pub fn add_i16(acc: &mut [i16], weights: &[i16]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(32));
+
+    for (value, weight) in acc.chunks_exact_mut(32).zip(weights.chunks_exact(32)) {
+        unsafe {
+            let sum = _mm512_add_epi16(load_i16(value), load_i16(weight));
+            _mm512_storeu_si512(value.as_mut_ptr() as *mut __m512i, sum);
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX-512BW. `acc` and `weights` have to be equally long, a multiple of 32 values.
+#[target_feature(enable = "avx512bw")]
+pub fn sub_i16(acc: &mut [i16], weights: &[i16]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(32));
+
+    for (value, weight) in acc.chunks_exact_mut(32).zip(weights.chunks_exact(32)) {
+        unsafe {
+            let difference = _mm512_sub_epi16(load_i16(value), load_i16(weight));
+            _mm512_storeu_si512(value.as_mut_ptr() as *mut __m512i, difference);
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support AVX-512BW. `inputs` and `weights` have to be equally long, a multiple of 32 values.
+#[target_feature(enable = "avx512bw")]
+pub fn screlu_dot(inputs: &[i16], weights: &[i16], max: i16) -> i32 {
+    debug_assert!(inputs.len() == weights.len() && inputs.len().is_multiple_of(32));
+
+    unsafe {
+        let zero = _mm512_setzero_si512();
+        let max = _mm512_set1_epi16(max);
+        let mut sum = _mm512_setzero_si512();
+
+        for (input, weight) in inputs.chunks_exact(32).zip(weights.chunks_exact(32)) {
+            let activated = _mm512_min_epi16(_mm512_max_epi16(load_i16(input), zero), max);
+            let weight = load_i16(weight);
+
+            for (activated, weight) in [
+                (_mm512_castsi512_si256(activated), _mm512_castsi512_si256(weight)),
+                (_mm512_extracti64x4_epi64(activated, 1), _mm512_extracti64x4_epi64(weight, 1)),
+            ] {
+                let activated = _mm512_cvtepi16_epi32(activated);
+                let squared = _mm512_mullo_epi32(activated, activated);
+                sum = _mm512_add_epi32(sum, _mm512_mullo_epi32(squared, _mm512_cvtepi16_epi32(weight)));
+            }
+        }
+
+        _mm512_reduce_add_epi32(sum)
+    }
+}
+
+#[inline]
+#[target_feature(enable = "avx512bw")]
+unsafe fn load_i16(values: &[i16]) -> __m512i {
+    _mm512_loadu_si512(values.as_ptr() as *const __m512i)
+}
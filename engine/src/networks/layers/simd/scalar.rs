@@ -0,0 +1,60 @@
+//-----------------------------------------------
+// Reference kernels. Every vectorised kernel has to produce exactly these results,
+// so integer math wraps and the f32 dot product keeps 8 partial sums reduced in a fixed order.
+//-----------------------------------------------
+
+pub fn add_i16(acc: &mut [i16], weights: &[i16]) {
+    for (value, &weight) in acc.iter_mut().zip(weights) {
+        *value = value.wrapping_add(weight);
+    }
+}
+
+pub fn sub_i16(acc: &mut [i16], weights: &[i16]) {
+    for (value, &weight) in acc.iter_mut().zip(weights) {
+        *value = value.wrapping_sub(weight);
+    }
+}
+
+pub fn add_f32(acc: &mut [f32], weights: &[f32]) {
+    for (value, &weight) in acc.iter_mut().zip(weights) {
+        *value += weight;
+    }
+}
+
+pub fn sub_f32(acc: &mut [f32], weights: &[f32]) {
+    for (value, &weight) in acc.iter_mut().zip(weights) {
+        *value -= weight;
+    }
+}
+
+pub fn madd_f32(acc: &mut [f32], mul: f32, weights: &[f32]) {
+    for (value, &weight) in acc.iter_mut().zip(weights) {
+        *value += mul * weight;
+    }
+}
+
+pub fn screlu_dot(inputs: &[i16], weights: &[i16], max: i16) -> i32 {
+    let mut result = 0i32;
+
+    for (&input, &weight) in inputs.iter().zip(weights) {
+        let activated = i32::from(input.clamp(0, max));
+        result = result.wrapping_add((activated * activated).wrapping_mul(i32::from(weight)));
+    }
+
+    result
+}
+
+pub fn relu_dot(a: &[f32], b: &[f32]) -> f32 {
+    debug_assert!(a.len() == b.len() && a.len().is_multiple_of(8));
+
+    let mut partial = [0.0f32; 8];
+
+    for (chunk_a, chunk_b) in a.chunks_exact(8).zip(b.chunks_exact(8)) {
+        for lane in 0..8 {
+            partial[lane] += chunk_a[lane].max(0.0) * chunk_b[lane].max(0.0);
+        }
+    }
+
+    let quad = [partial[0] + partial[4], partial[1] + partial[5], partial[2] + partial[6], partial[3] + partial[7]];
+    (quad[0] + quad[2]) + (quad[1] + quad[3])
+}
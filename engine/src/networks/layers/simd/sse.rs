@@ -0,0 +1,139 @@
+use std::arch::x86_64::*;
+
+//x86-64-v2 kernels, SSE4.1 is the newest extension guaranteed there.
+
+/// # Safety
+/// The CPU has to support SSE4.1. `acc` and `weights` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "sse4.1")]
+pub fn add_i16(acc: &mut [i16], weights: &[i16]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(8));
+
+    for (value, weight) in acc.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+        unsafe {
+            let sum = _mm_add_epi16(load_i16(value), load_i16(weight));
+            _mm_storeu_si128(value.as_mut_ptr() as *mut __m128i, sum);
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support SSE4.1. `acc` and `weights` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "sse4.1")]
+pub fn sub_i16(acc: &mut [i16], weights: &[i16]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(8));
+
+    for (value, weight) in acc.chunks_exact_mut(8).zip(weights.chunks_exact(8)) {
+        unsafe {
+            let difference = _mm_sub_epi16(load_i16(value), load_i16(weight));
+            _mm_storeu_si128(value.as_mut_ptr() as *mut __m128i, difference);
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support SSE4.1. `acc` and `weights` have to be equally long, a multiple of 4 values.
+#[target_feature(enable = "sse4.1")]
+pub fn add_f32(acc: &mut [f32], weights: &[f32]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(4));
+
+    for (value, weight) in acc.chunks_exact_mut(4).zip(weights.chunks_exact(4)) {
+        unsafe { _mm_storeu_ps(value.as_mut_ptr(), _mm_add_ps(_mm_loadu_ps(value.as_ptr()), _mm_loadu_ps(weight.as_ptr()))) }
+    }
+}
+
+/// # Safety
+/// The CPU has to support SSE4.1. `acc` and `weights` have to be equally long, a multiple of 4 values.
+#[target_feature(enable = "sse4.1")]
+pub fn sub_f32(acc: &mut [f32], weights: &[f32]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(4));
+
+    for (value, weight) in acc.chunks_exact_mut(4).zip(weights.chunks_exact(4)) {
+        unsafe { _mm_storeu_ps(value.as_mut_ptr(), _mm_sub_ps(_mm_loadu_ps(value.as_ptr()), _mm_loadu_ps(weight.as_ptr()))) }
+    }
+}
+
+/// # Safety
+/// The CPU has to support SSE4.1. `acc` and `weights` have to be equally long, a multiple of 4 values.
+#[target_feature(enable = "sse4.1")]
+pub fn madd_f32(acc: &mut [f32], mul: f32, weights: &[f32]) {
+    debug_assert!(acc.len() == weights.len() && acc.len().is_multiple_of(4));
+
+    unsafe {
+        let mul = _mm_set1_ps(mul);
+
+        for (value, weight) in acc.chunks_exact_mut(4).zip(weights.chunks_exact(4)) {
+            let product = _mm_mul_ps(mul, _mm_loadu_ps(weight.as_ptr()));
+            _mm_storeu_ps(value.as_mut_ptr(), _mm_add_ps(_mm_loadu_ps(value.as_ptr()), product));
+        }
+    }
+}
+
+/// # Safety
+/// The CPU has to support SSE4.1. `inputs` and `weights` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "sse4.1")]
+pub fn screlu_dot(inputs: &[i16], weights: &[i16], max: i16) -> i32 {
+    debug_assert!(inputs.len() == weights.len() && inputs.len().is_multiple_of(8));
+
+    unsafe {
+        let zero = _mm_setzero_si128();
+        let max = _mm_set1_epi16(max);
+        let mut sum = _mm_setzero_si128();
+
+        for (input, weight) in inputs.chunks_exact(8).zip(weights.chunks_exact(8)) {
+            let activated = _mm_min_epi16(_mm_max_epi16(load_i16(input), zero), max);
+            let weight = load_i16(weight);
+
+            for (activated, weight) in [
+                (_mm_cvtepi16_epi32(activated), _mm_cvtepi16_epi32(weight)),
+                (_mm_cvtepi16_epi32(_mm_srli_si128(activated, 8)), _mm_cvtepi16_epi32(_mm_srli_si128(weight, 8))),
+            ] {
+                let squared = _mm_mullo_epi32(activated, activated);
+                sum = _mm_add_epi32(sum, _mm_mullo_epi32(squared, weight));
+            }
+        }
+
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32(sum, 0b01_00_11_10));
+        let sum = _mm_add_epi32(sum, _mm_shuffle_epi32(sum, 0b10_11_00_01));
+        _mm_cvtsi128_si32(sum)
+    }
+}
+
+/// # Safety
+/// The CPU has to support SSE4.1. `a` and `b` have to be equally long, a multiple of 8 values.
+#[target_feature(enable = "sse4.1")]
+pub fn relu_dot(a: &[f32], b: &[f32]) -> f32 {
+    debug_assert!(a.len() == b.len() && a.len().is_multiple_of(8));
+
+    unsafe {
+        let zero = _mm_setzero_ps();
+        let mut low = _mm_setzero_ps();
+        let mut high = _mm_setzero_ps();
+
+        for (chunk_a, chunk_b) in a.chunks_exact(8).zip(b.chunks_exact(8)) {
+            let a_low = _mm_max_ps(_mm_loadu_ps(chunk_a.as_ptr()), zero);
+            let a_high = _mm_max_ps(_mm_loadu_ps(chunk_a.as_ptr().add(4)), zero);
+            let b_low = _mm_max_ps(_mm_loadu_ps(chunk_b.as_ptr()), zero);
+            let b_high = _mm_max_ps(_mm_loadu_ps(chunk_b.as_ptr().add(4)), zero);
+
+            low = _mm_add_ps(low, _mm_mul_ps(a_low, b_low));
+            high = _mm_add_ps(high, _mm_mul_ps(a_high, b_high));
+        }
+
+        reduce_8(low, high)
+    }
+}
+
+//Same reduction order as the scalar reference: lanes i and i + 4, then i and i + 2, then the last pair.
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn reduce_8(low: __m128, high: __m128) -> f32 {
+    let quad = _mm_add_ps(low, high);
+    let pair = _mm_add_ps(quad, _mm_movehl_ps(quad, quad));
+    _mm_cvtss_f32(_mm_add_ss(pair, _mm_shuffle_ps(pair, pair, 0b01)))
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn load_i16(values: &[i16]) -> __m128i {
+    _mm_loadu_si128(values.as_ptr() as *const __m128i)
+}
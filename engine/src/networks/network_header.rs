@@ -0,0 +1,189 @@
+use crate::networks::{policy_network, quantised_policy_network, value_network, Standard768, Threats3072};
+
+//64 byte header in front of every network file loaded at runtime
+//0..4   -> magic "JNET"
+//4..6   -> header version
+//6      -> architecture id
+//7      -> input set id
+//8..12  -> input size
+//12..16 -> hidden size
+//16..20 -> output size
+//20..22 -> output buckets (value) or subnets (policy)
+//22..24 -> QA, zero for float networks
+//24..26 -> QB, zero for float networks
+//26..32 -> reserved
+//32..40 -> payload size in bytes
+//40..48 -> FNV-1a checksum of the payload
+//48..64 -> reserved
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetworkHeader {
+    version: u16,
+    architecture: u8,
+    input_set: u8,
+    input_size: u32,
+    hidden_size: u32,
+    output_size: u32,
+    buckets: u16,
+    qa: i16,
+    qb: i16,
+    payload_size: u64,
+    checksum: u64,
+}
+
+impl NetworkHeader {
+    pub const SIZE: usize = 64;
+    pub const MAGIC: [u8; 4] = *b"JNET";
+    pub const VERSION: u16 = 1;
+
+    pub const VALUE_ARCHITECTURE: u8 = 0;
+    pub const POLICY_ARCHITECTURE: u8 = 1;
+    pub const QUANTISED_POLICY_ARCHITECTURE: u8 = 2;
+
+    pub const STANDARD_768: u8 = 0;
+    pub const THREATS_3072: u8 = 1;
+
+    //Header describing the value network this engine is compiled with.
+    pub fn value(payload: &[u8]) -> Self {
+        Self {
+            version: Self::VERSION,
+            architecture: Self::VALUE_ARCHITECTURE,
+            input_set: Self::THREATS_3072,
+            input_size: Threats3072::input_size() as u32,
+            hidden_size: value_network::L1_SIZE as u32,
+            output_size: 3 * value_network::NUM_OUTPUT_BUCKETS as u32,
+            buckets: value_network::NUM_OUTPUT_BUCKETS as u16,
+            qa: value_network::QA,
+            qb: value_network::QB,
+            payload_size: payload.len() as u64,
+            checksum: checksum(payload),
+        }
+    }
+
+    //Header describing the policy network this engine is compiled with.
+    pub fn policy(payload: &[u8]) -> Self {
+        Self {
+            version: Self::VERSION,
+            architecture: Self::POLICY_ARCHITECTURE,
+            input_set: Self::STANDARD_768,
+            input_size: Standard768::input_size() as u32,
+            hidden_size: policy_network::HIDDEN_SIZE as u32,
+            output_size: policy_network::HIDDEN_SIZE as u32,
+            buckets: policy_network::SUBNET_COUNT as u16,
+            qa: 0,
+            qb: 0,
+            payload_size: payload.len() as u64,
+            checksum: checksum(payload),
+        }
+    }
+
+    //Header describing the quantised form of the policy network, the one search runs on.
+    pub fn quantised_policy(payload: &[u8]) -> Self {
+        Self {
+            architecture: Self::QUANTISED_POLICY_ARCHITECTURE,
+            qa: quantised_policy_network::QA,
+            qb: quantised_policy_network::QB,
+            ..Self::policy(payload)
+        }
+    }
+
+    #[inline]
+    pub fn architecture(&self) -> u8 {
+        self.architecture
+    }
+
+    #[inline]
+    pub fn payload_size(&self) -> usize {
+        self.payload_size as usize
+    }
+
+    #[inline]
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut result = [0u8; Self::SIZE];
+
+        result[0..4].copy_from_slice(&Self::MAGIC);
+        result[4..6].copy_from_slice(&self.version.to_le_bytes());
+        result[6] = self.architecture;
+        result[7] = self.input_set;
+        result[8..12].copy_from_slice(&self.input_size.to_le_bytes());
+        result[12..16].copy_from_slice(&self.hidden_size.to_le_bytes());
+        result[16..20].copy_from_slice(&self.output_size.to_le_bytes());
+        result[20..22].copy_from_slice(&self.buckets.to_le_bytes());
+        result[22..24].copy_from_slice(&self.qa.to_le_bytes());
+        result[24..26].copy_from_slice(&self.qb.to_le_bytes());
+        result[32..40].copy_from_slice(&self.payload_size.to_le_bytes());
+        result[40..48].copy_from_slice(&self.checksum.to_le_bytes());
+
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < Self::SIZE || bytes[0..4] != Self::MAGIC {
+            return Err(String::from("Missing network header"));
+        }
+
+        let u16_at = |idx: usize| u16::from_le_bytes([bytes[idx], bytes[idx + 1]]);
+        let u32_at = |idx: usize| u32::from_le_bytes(bytes[idx..idx + 4].try_into().unwrap());
+        let u64_at = |idx: usize| u64::from_le_bytes(bytes[idx..idx + 8].try_into().unwrap());
+
+        Ok(Self {
+            version: u16_at(4),
+            architecture: bytes[6],
+            input_set: bytes[7],
+            input_size: u32_at(8),
+            hidden_size: u32_at(12),
+            output_size: u32_at(16),
+            buckets: u16_at(20),
+            qa: u16_at(22) as i16,
+            qb: u16_at(24) as i16,
+            payload_size: u64_at(32),
+            checksum: u64_at(40),
+        })
+    }
+
+    //Compares against the header the engine expects and names the first field that differs.
+    pub fn validate(&self, expected: &Self) -> Result<(), String> {
+        let fields = [
+            ("version", u64::from(self.version), u64::from(expected.version)),
+            ("architecture", u64::from(self.architecture), u64::from(expected.architecture)),
+            ("input set", u64::from(self.input_set), u64::from(expected.input_set)),
+            ("input size", u64::from(self.input_size), u64::from(expected.input_size)),
+            ("hidden size", u64::from(self.hidden_size), u64::from(expected.hidden_size)),
+            ("output size", u64::from(self.output_size), u64::from(expected.output_size)),
+            ("buckets", u64::from(self.buckets), u64::from(expected.buckets)),
+            ("QA", self.qa as u64, expected.qa as u64),
+            ("QB", self.qb as u64, expected.qb as u64),
+            ("payload size", self.payload_size, expected.payload_size),
+        ];
+
+        for (name, found, expected) in fields {
+            if found != expected {
+                return Err(format!("Network {name} mismatch: file has {found}, engine expects {expected}"));
+            }
+        }
+
+        if self.checksum != expected.checksum {
+            return Err(format!(
+                "Network checksum mismatch: header has {:016x}, payload hashes to {:016x}",
+                self.checksum, expected.checksum
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    hash
+}
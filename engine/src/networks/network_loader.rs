@@ -0,0 +1,178 @@
+use std::{
+    alloc::{alloc_zeroed, handle_alloc_error, Layout},
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        LazyLock, Mutex,
+    },
+};
+
+use crate::networks::{
+    network_header::NetworkHeader, policy_network::PolicyNetwork, quantised_policy_network::QuantisedPolicyNetwork,
+    value_network::ValueNetwork,
+};
+
+//-----------------------------------------------
+// Networks used by the search. They point at the embedded nets until a file is loaded
+// through EvalFile/PolicyFile. A replaced net is kept until free_replaced_networks,
+// search threads can still hold a reference to it while it is being replaced.
+//-----------------------------------------------
+
+static CURRENT_VALUE_NETWORK: AtomicPtr<ValueNetwork> = AtomicPtr::new(&super::ValueNetwork as *const ValueNetwork as *mut ValueNetwork);
+
+//Null means the embedded policy, which is only stored in f32 and gets quantised on first use.
+static CURRENT_POLICY_NETWORK: AtomicPtr<QuantisedPolicyNetwork> = AtomicPtr::new(std::ptr::null_mut());
+static EMBEDDED_POLICY_NETWORK: LazyLock<Box<QuantisedPolicyNetwork>> = LazyLock::new(|| {
+    let (network, clamped) = QuantisedPolicyNetwork::from_float(&super::PolicyNetwork);
+    assert_eq!(clamped, 0, "embedded policy has values outside of the quantised range");
+    network
+});
+
+static REPLACED_VALUE_NETWORKS: Mutex<Vec<Replaced<ValueNetwork>>> = Mutex::new(Vec::new());
+static REPLACED_POLICY_NETWORKS: Mutex<Vec<Replaced<QuantisedPolicyNetwork>>> = Mutex::new(Vec::new());
+
+//Loaded net that is no longer current, allocated by Box::into_raw
+struct Replaced<T>(*mut T);
+unsafe impl<T> Send for Replaced<T> {}
+
+#[inline]
+pub fn value_network() -> &'static ValueNetwork {
+    unsafe { &*CURRENT_VALUE_NETWORK.load(Ordering::Acquire) }
+}
+
+#[inline]
+pub fn policy_network() -> &'static QuantisedPolicyNetwork {
+    let network = CURRENT_POLICY_NETWORK.load(Ordering::Acquire);
+
+    if network.is_null() {
+        &EMBEDDED_POLICY_NETWORK
+    } else {
+        unsafe { &*network }
+    }
+}
+
+//Empty path restores the embedded net. On error the embedded net is restored as well.
+pub fn load_value_network(path: &str) -> Result<(), String> {
+    let previous = CURRENT_VALUE_NETWORK.swap(&super::ValueNetwork as *const ValueNetwork as *mut ValueNetwork, Ordering::AcqRel);
+    if !std::ptr::eq(previous, &super::ValueNetwork) {
+        REPLACED_VALUE_NETWORKS.lock().unwrap().push(Replaced(previous));
+    }
+
+    if path.is_empty() || path == "<empty>" {
+        return Ok(());
+    }
+
+    let (header, payload) = read_network(path)?;
+    let network = from_payload::<ValueNetwork>(path, &header, &payload, NetworkHeader::value)?;
+
+    CURRENT_VALUE_NETWORK.store(Box::into_raw(network), Ordering::Release);
+    Ok(())
+}
+
+//Accepts both float and quantised policy files, float ones are quantised while loading.
+//Empty path restores the embedded net. On error the embedded net is restored as well.
+pub fn load_policy_network(path: &str) -> Result<(), String> {
+    let previous = CURRENT_POLICY_NETWORK.swap(std::ptr::null_mut(), Ordering::AcqRel);
+    if !previous.is_null() {
+        REPLACED_POLICY_NETWORKS.lock().unwrap().push(Replaced(previous));
+    }
+
+    if path.is_empty() || path == "<empty>" {
+        return Ok(());
+    }
+
+    let network = read_policy_network(path)?;
+
+    CURRENT_POLICY_NETWORK.store(Box::into_raw(network), Ordering::Release);
+    Ok(())
+}
+
+//Frees every net replaced by a load so far.
+//Safety: no reference returned by value_network or policy_network may be alive, so no search can be running.
+pub(crate) unsafe fn free_replaced_networks() {
+    for Replaced(network) in REPLACED_VALUE_NETWORKS.lock().unwrap().drain(..) {
+        drop(Box::from_raw(network));
+    }
+
+    for Replaced(network) in REPLACED_POLICY_NETWORKS.lock().unwrap().drain(..) {
+        drop(Box::from_raw(network));
+    }
+}
+
+//Converts a float policy file into a quantised one, both with a header.
+//Fails when any value would have to be clamped to fit the quantised range.
+pub fn quantise_policy_file(input: &str, output: &str) -> Result<(), String> {
+    let (header, payload) = read_network(input)?;
+
+    if header.architecture() != NetworkHeader::POLICY_ARCHITECTURE {
+        return Err(format!("'{input}' is not a float policy network"));
+    }
+
+    let network = quantise_policy(input, &*from_payload::<PolicyNetwork>(input, &header, &payload, NetworkHeader::policy)?)?;
+    let payload = unsafe {
+        std::slice::from_raw_parts(&*network as *const QuantisedPolicyNetwork as *const u8, std::mem::size_of::<QuantisedPolicyNetwork>())
+    };
+
+    let mut bytes = NetworkHeader::quantised_policy(payload).to_bytes().to_vec();
+    bytes.extend_from_slice(payload);
+
+    std::fs::write(output, bytes).map_err(|err| format!("Failed to write '{output}': {err}"))
+}
+
+fn read_policy_network(path: &str) -> Result<Box<QuantisedPolicyNetwork>, String> {
+    let (header, payload) = read_network(path)?;
+
+    if header.architecture() == NetworkHeader::POLICY_ARCHITECTURE {
+        let network = from_payload::<PolicyNetwork>(path, &header, &payload, NetworkHeader::policy)?;
+        quantise_policy(path, &network).map_err(|err| format!("{err}. Using embedded network."))
+    } else {
+        from_payload::<QuantisedPolicyNetwork>(path, &header, &payload, NetworkHeader::quantised_policy)
+    }
+}
+
+fn quantise_policy(path: &str, network: &PolicyNetwork) -> Result<Box<QuantisedPolicyNetwork>, String> {
+    match QuantisedPolicyNetwork::from_float(network) {
+        (network, 0) => Ok(network),
+        (_, clamped) => Err(format!("'{path}' has {clamped} values outside of the quantised range")),
+    }
+}
+
+fn read_network(path: &str) -> Result<(NetworkHeader, Vec<u8>), String> {
+    let mut bytes = std::fs::read(path).map_err(|err| format!("Failed to read '{path}': {err}. Using embedded network."))?;
+    let header = NetworkHeader::from_bytes(&bytes).map_err(|err| format!("'{path}': {err}. Using embedded network."))?;
+
+    Ok((header, bytes.split_off(NetworkHeader::SIZE)))
+}
+
+fn from_payload<T>(path: &str, header: &NetworkHeader, payload: &[u8], expected_header: fn(&[u8]) -> NetworkHeader) -> Result<Box<T>, String> {
+    header
+        .validate(&expected_header(payload))
+        .map_err(|err| format!("'{path}': {err}. Using embedded network."))?;
+
+    if payload.len() != std::mem::size_of::<T>() {
+        return Err(format!(
+            "'{path}' has {} payload bytes, expected {}. Using embedded network.",
+            payload.len(),
+            std::mem::size_of::<T>()
+        ));
+    }
+
+    let mut network = boxed_zeroed::<T>();
+    unsafe { std::ptr::copy_nonoverlapping(payload.as_ptr(), &mut *network as *mut T as *mut u8, payload.len()) };
+
+    Ok(network)
+}
+
+//Networks are too large for the stack, so they are built straight in a zeroed heap allocation.
+//Only valid for network types, which are plain arrays of numbers.
+pub(super) fn boxed_zeroed<T>() -> Box<T> {
+    let layout = Layout::new::<T>();
+
+    unsafe {
+        let network = alloc_zeroed(layout);
+        if network.is_null() {
+            handle_alloc_error(layout);
+        }
+
+        Box::from_raw(network as *mut T)
+    }
+}
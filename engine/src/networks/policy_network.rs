@@ -1,85 +1,76 @@
 use chess::{ChessBoard, Move, Side};
 
-use crate::networks::{inputs::Standard768, layers::NetworkLayer};
+use crate::networks::{inputs::Standard768, layers::{simd, NetworkLayer}};
 
-const INPUT_SIZE: usize = Standard768::input_size();
+pub(super) const INPUT_SIZE: usize = Standard768::input_size();
+pub(super) const HIDDEN_SIZE: usize = 32;
+pub(super) const SUBNET_COUNT: usize = 192;
+
+const SEE_THRESHOLD: i32 = -108;
+
+//Float policy network as produced by the trainer. Search runs on QuantisedPolicyNetwork,
+//this layout only exists to read float files, quantise them and check the quantised outputs.
 
 #[repr(C)]
 #[derive(Debug)]
 pub struct PolicyNetwork {
-    subnets: [PolicyNetworkSubnet; 192],
+    subnets: [PolicyNetworkSubnet; SUBNET_COUNT],
 }
 
 #[repr(C)]
 #[derive(Debug)]
 pub struct PolicyNetworkSubnet {
-    l0: NetworkLayer<f32, INPUT_SIZE, 32>,
-    l1: NetworkLayer<f32, 32, 32>,
+    l0: NetworkLayer<f32, INPUT_SIZE, HIDDEN_SIZE>,
+    l1: NetworkLayer<f32, HIDDEN_SIZE, HIDDEN_SIZE>,
 }
 
 impl PolicyNetwork {
-    pub fn get_inputs(&self, board: &ChessBoard) -> Vec<usize> {
-        let mut result = Vec::with_capacity(board.occupancy().pop_count() as usize);
-        Standard768::map_inputs(board, |idx| result.push(idx));
-        result
+    #[inline]
+    pub fn subnets(&self) -> &[PolicyNetworkSubnet; SUBNET_COUNT] {
+        &self.subnets
     }
 
-    pub fn forward(&self, board: &ChessBoard, inputs: &Vec<usize>, mv: Move, cache: &mut [Option<Vec<f32>>; 192]) -> f32 {
-        let see_idx = usize::from(board.see(mv, -108));
-        let vertical_flip = (usize::from(board.side() == Side::BLACK) * 56) as u8;
-
-        let from_idx = usize::from(mv.get_from_square() ^ vertical_flip);
-        let to_idx = usize::from(mv.get_to_square() ^ vertical_flip) + 64 + see_idx * 64;
-
-        let from = if let Some(from) = &cache[from_idx] {
-            from.clone()
-        } else {
-            let result = self.subnets[from_idx].forward(inputs);
-            cache[from_idx] = Some(result.clone());
-            result
-        };
-        
-        let to = if let Some(to) = &cache[to_idx] {
-            to.clone()
-        } else {
-            let result = self.subnets[to_idx].forward(inputs);
-            cache[to_idx] = Some(result.clone());
-            result
-        };
-
-        dot(&from, &to)
+    //Float reference of QuantisedPolicyNetwork::forward
+    pub fn forward(&self, board: &ChessBoard, inputs: &[usize], mv: Move) -> f32 {
+        let (from_idx, to_idx) = subnet_indices(board, mv);
+        simd::relu_dot(&self.subnets[from_idx].forward(inputs), &self.subnets[to_idx].forward(inputs))
     }
 }
 
 impl PolicyNetworkSubnet {
-    pub fn forward(&self, inputs: &Vec<usize>) -> Vec<f32> {
+    #[inline]
+    pub fn l0(&self) -> &NetworkLayer<f32, INPUT_SIZE, HIDDEN_SIZE> {
+        &self.l0
+    }
+
+    #[inline]
+    pub fn l1(&self) -> &NetworkLayer<f32, HIDDEN_SIZE, HIDDEN_SIZE> {
+        &self.l1
+    }
+
+    fn forward(&self, inputs: &[usize]) -> [f32; HIDDEN_SIZE] {
         let mut l0_out = *self.l0.biases();
 
         for &input_index in inputs {
-            for (bias, weight) in l0_out.values_mut().iter_mut().zip(self.l0.weights()[input_index].values()) {
-                *bias += *weight;
-            }
+            l0_out.add(&self.l0.weights()[input_index]);
         }
 
         let mut out = *self.l1.biases();
-        for (neuron, weights) in l0_out.values().iter().zip(self.l1.weights().iter()) {
-            out.madd(relu(*neuron), weights);
+        for (&neuron, weights) in l0_out.values().iter().zip(self.l1.weights()) {
+            out.madd(neuron.max(0.0), weights);
         }
 
-        out.values().to_vec()
+        *out.values()
     }
 }
 
-fn dot(a: &Vec<f32>, b: &Vec<f32>) -> f32 {
-    let mut result = 0.0;
+//Subnets of the move's from square and of its to square, the latter split by whether the move passes SEE
+pub(super) fn subnet_indices(board: &ChessBoard, mv: Move) -> (usize, usize) {
+    let see_idx = usize::from(board.see(mv, SEE_THRESHOLD));
+    let vertical_flip = (usize::from(board.side() == Side::BLACK) * 56) as u8;
 
-    for (value_a, value_b) in a.iter().zip(b) {
-        result += relu(*value_a) * relu(*value_b)
-    }
+    let from_idx = usize::from(mv.get_from_square() ^ vertical_flip);
+    let to_idx = usize::from(mv.get_to_square() ^ vertical_flip) + 64 + see_idx * 64;
 
-    result
+    (from_idx, to_idx)
 }
-
-fn relu(x: f32) -> f32 {
-    x.max(0.0)
-}
\ No newline at end of file
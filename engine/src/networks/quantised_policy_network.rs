@@ -0,0 +1,136 @@
+use chess::{ChessBoard, Move};
+
+use crate::networks::{
+    inputs::Standard768,
+    layers::{simd, Accumulator, NetworkLayer},
+    network_loader::boxed_zeroed,
+    policy_network::{subnet_indices, PolicyNetwork, HIDDEN_SIZE, INPUT_SIZE, SUBNET_COUNT},
+};
+
+pub(super) const QA: i16 = 255;
+pub(super) const QB: i16 = 64;
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct QuantisedPolicyNetwork {
+    subnets: [QuantisedPolicySubnet; SUBNET_COUNT],
+}
+
+//l0 is quantised by QA, l1 weights by QB and l1 biases by QA * QB.
+#[repr(C)]
+#[derive(Debug)]
+pub struct QuantisedPolicySubnet {
+    l0: NetworkLayer<i16, INPUT_SIZE, HIDDEN_SIZE>,
+    l1_weights: [Accumulator<i8, HIDDEN_SIZE>; HIDDEN_SIZE],
+    l1_biases: Accumulator<i32, HIDDEN_SIZE>,
+}
+
+//Subnet outputs of the position being expanded, every subnet is evaluated at most once.
+pub struct PolicyCache {
+    outputs: [[f32; HIDDEN_SIZE]; SUBNET_COUNT],
+    computed: [bool; SUBNET_COUNT],
+}
+
+impl Default for PolicyCache {
+    fn default() -> Self {
+        Self {
+            outputs: [[0.0; HIDDEN_SIZE]; SUBNET_COUNT],
+            computed: [false; SUBNET_COUNT],
+        }
+    }
+}
+
+impl QuantisedPolicyNetwork {
+    //Also returns how many values fell outside the quantised range and were clamped
+    pub fn from_float(network: &PolicyNetwork) -> (Box<Self>, usize) {
+        let mut result = boxed_zeroed::<Self>();
+        let mut clamped = 0;
+
+        for (subnet, float_subnet) in result.subnets.iter_mut().zip(network.subnets()) {
+            for (row, float_row) in subnet.l0.weights_mut().iter_mut().zip(float_subnet.l0().weights()) {
+                clamped += quantise(row.values_mut(), float_row.values(), f32::from(QA));
+            }
+
+            clamped += quantise(subnet.l0.biases_mut().values_mut(), float_subnet.l0().biases().values(), f32::from(QA));
+
+            for (row, float_row) in subnet.l1_weights.iter_mut().zip(float_subnet.l1().weights()) {
+                clamped += quantise(row.values_mut(), float_row.values(), f32::from(QB));
+            }
+
+            clamped += quantise(subnet.l1_biases.values_mut(), float_subnet.l1().biases().values(), f32::from(QA) * f32::from(QB));
+        }
+
+        (result, clamped)
+    }
+
+    pub fn get_inputs(&self, board: &ChessBoard) -> Vec<usize> {
+        let mut result = Vec::with_capacity(board.occupancy().pop_count() as usize);
+        Standard768::map_inputs(board, |idx| result.push(idx));
+        result
+    }
+
+    pub fn forward(&self, board: &ChessBoard, inputs: &[usize], mv: Move, cache: &mut PolicyCache) -> f32 {
+        let (from_idx, to_idx) = subnet_indices(board, mv);
+
+        for subnet_idx in [from_idx, to_idx] {
+            if !cache.computed[subnet_idx] {
+                cache.outputs[subnet_idx] = self.subnets[subnet_idx].forward(inputs);
+                cache.computed[subnet_idx] = true;
+            }
+        }
+
+        simd::relu_dot(&cache.outputs[from_idx], &cache.outputs[to_idx])
+    }
+}
+
+impl QuantisedPolicySubnet {
+    fn forward(&self, inputs: &[usize]) -> [f32; HIDDEN_SIZE] {
+        let mut l0_out = *self.l0.biases();
+
+        for &input_index in inputs {
+            l0_out.add(&self.l0.weights()[input_index]);
+        }
+
+        let mut out = *self.l1_biases.values();
+        for (&neuron, weights) in l0_out.values().iter().zip(self.l1_weights.iter()) {
+            let neuron = i32::from(neuron.max(0));
+
+            for (output, &weight) in out.iter_mut().zip(weights.values()) {
+                *output += neuron * i32::from(weight);
+            }
+        }
+
+        out.map(|output| output as f32 / (f32::from(QA) * f32::from(QB)))
+    }
+}
+
+//Rounds to the nearest step and saturates at the bounds of T. Returns the number of saturated values.
+fn quantise<T: TryFrom<i64> + Bounded>(target: &mut [T], source: &[f32], scale: f32) -> usize {
+    let mut clamped = 0;
+
+    for (value, &float) in target.iter_mut().zip(source) {
+        let rounded = (float * scale).round() as i64;
+        let quantised = rounded.clamp(T::MIN, T::MAX);
+
+        clamped += usize::from(quantised != rounded);
+        *value = T::try_from(quantised).ok().unwrap();
+    }
+
+    clamped
+}
+
+trait Bounded {
+    const MIN: i64;
+    const MAX: i64;
+}
+
+macro_rules! bounded {
+    ($($ty:ty),+) => {
+        $(impl Bounded for $ty {
+            const MIN: i64 = <$ty>::MIN as i64;
+            const MAX: i64 = <$ty>::MAX as i64;
+        })+
+    };
+}
+
+bounded!(i8, i16, i32);
@@ -1,13 +1,18 @@
 use chess::ChessBoard;
 
-use crate::{networks::{inputs::Threats3072, layers::{Accumulator, NetworkLayer, TransposedNetworkLayer}}, WDLScore};
+use crate::{networks::{inputs::Threats3072, layers::{simd, Accumulator, NetworkLayer, TransposedNetworkLayer}}, WDLScore};
 
-const INPUT_SIZE: usize = Threats3072::input_size();
-const L1_SIZE: usize = 3072;
-const NUM_OUTPUT_BUCKETS: usize = 8;
+mod accumulator_stack;
+mod refresh_table;
 
-const QA: i16 = 255;
-const QB: i16 = 64;
+pub use accumulator_stack::AccumulatorStack;
+
+pub(super) const INPUT_SIZE: usize = Threats3072::input_size();
+pub(super) const L1_SIZE: usize = 3072;
+pub(super) const NUM_OUTPUT_BUCKETS: usize = 8;
+
+pub(super) const QA: i16 = 255;
+pub(super) const QB: i16 = 64;
 
 #[repr(C)]
 #[repr(align(64))]
@@ -22,11 +27,13 @@ impl ValueNetwork {
         let mut l0_out = *self.l0.biases();
 
         Threats3072::map_inputs(board, |input_index| {
-            for (bias, weight) in l0_out.values_mut().iter_mut().zip(self.l0.weights()[input_index].values()) {
-                *bias += *weight
-            }
+            l0_out.add(&self.l0.weights()[input_index]);
         });
 
+        self.output(board, &l0_out)
+    }
+
+    fn output(&self, board: &ChessBoard, l0_out: &Accumulator<i16, L1_SIZE>) -> WDLScore {
         let mut out = Accumulator::<i32, 3>::default();
 
         let bucket_idx = {
@@ -35,11 +42,7 @@ impl ValueNetwork {
         } * 3;
 
         for (idx, output) in out.values_mut().iter_mut().enumerate() {
-            let weights = self.l1.weights()[bucket_idx + idx];
-
-            for (&weight, &l0_neuron) in weights.values().iter().zip(l0_out.values()) {
-                *output += screlu(l0_neuron) * i32::from(weight);
-            }
+            *output = simd::screlu_dot(l0_out.values(), self.l1.weights()[bucket_idx + idx].values(), QA);
         }
 
         let mut win_chance = (out.values()[2] as f64 / f64::from(QA)
@@ -63,7 +66,3 @@ impl ValueNetwork {
         WDLScore::new(win_chance / sum, draw_chance / sum)
     }
 }
-
-fn screlu(x: i16) -> i32 {
-    i32::from(x).clamp(0, i32::from(QA)).pow(2)
-}
\ No newline at end of file
@@ -0,0 +1,143 @@
+use chess::ChessBoard;
+
+use crate::{networks::{inputs::Threats3072, layers::Accumulator}, WDLScore};
+
+use super::{ValueNetwork, INPUT_SIZE, L1_SIZE};
+
+pub(super) const FEATURE_WORDS: usize = INPUT_SIZE / 64;
+
+//-----------------------------------------------
+// Positions of the current selection path, from the root down to the leaf, with their L0
+// accumulators. Threats3072 inputs are side to move relative, so a position is updated from
+// the one two plies up with the feature delta of both moves. Threat and defence bits are part
+// of the feature index, so changed attacks show up in the delta like any moved piece. The
+// stack is kept between iterations and entries are only computed again when the path changes.
+//-----------------------------------------------
+
+pub struct AccumulatorStack {
+    network: *const ValueNetwork,
+    entries: Vec<StackEntry>,
+    len: usize,
+}
+
+struct StackEntry {
+    board: ChessBoard,
+    features: [u64; FEATURE_WORDS],
+    accumulator: Accumulator<i16, L1_SIZE>,
+    ready: bool,
+}
+
+impl Default for AccumulatorStack {
+    fn default() -> Self {
+        Self { network: std::ptr::null(), entries: Vec::new(), len: 0 }
+    }
+}
+
+impl AccumulatorStack {
+    //Starts a new path from the root position.
+    pub fn reset(&mut self, board: &ChessBoard) {
+        self.len = 0;
+        self.push(board);
+    }
+
+    //Adds the next position of the path. Accumulators are kept as long as the position at that ply stays the same.
+    pub fn push(&mut self, board: &ChessBoard) {
+        if let Some(entry) = self.entries.get_mut(self.len) {
+            if entry.board.hash() != board.hash() {
+                entry.board = *board;
+                entry.ready = false;
+            }
+        } else {
+            self.entries.push(StackEntry {
+                board: *board,
+                features: [0; FEATURE_WORDS],
+                accumulator: Accumulator::default(),
+                ready: false,
+            });
+        }
+
+        self.len += 1;
+    }
+}
+
+impl ValueNetwork {
+    //Same result as forward for the last position of the stack.
+    pub fn forward_stack(&self, stack: &mut AccumulatorStack) -> WDLScore {
+        //Accumulators belong to the network they were built with.
+        if !std::ptr::eq(stack.network, self) {
+            stack.network = self;
+            stack.entries.iter_mut().for_each(|entry| entry.ready = false);
+        }
+
+        let top = stack.len - 1;
+
+        //Closest ready position with the same side to move, or the first one of the path to build the chain from.
+        let mut base = top;
+        while !stack.entries[base].ready && base >= 2 {
+            base -= 2;
+        }
+
+        if !stack.entries[base].ready {
+            let entry = &mut stack.entries[base];
+            let feature_count = map_features(&entry.board, &mut entry.features);
+            entry.accumulator = self.refresh_l0(&entry.board, &entry.features, feature_count);
+            entry.ready = true;
+        }
+
+        for idx in (base + 2..=top).step_by(2) {
+            let (parents, children) = stack.entries.split_at_mut(idx);
+            let parent = &parents[idx - 2];
+            let entry = &mut children[0];
+
+            let feature_count = map_features(&entry.board, &mut entry.features);
+            entry.accumulator = parent.accumulator;
+
+            //A king crossing the mirror line changes nearly every feature, so the refresh table is closer.
+            if !self.update_l0(&mut entry.accumulator, &parent.features, &entry.features, feature_count) {
+                entry.accumulator = self.refresh_l0(&entry.board, &entry.features, feature_count);
+            }
+
+            entry.ready = true;
+        }
+
+        let entry = &stack.entries[top];
+        self.output(&entry.board, &entry.accumulator)
+    }
+
+    //Moves accumulator from the old feature set to the new one. Returns false without touching it when
+    //rebuilding from the biases would touch fewer weight rows.
+    pub(super) fn update_l0(&self, accumulator: &mut Accumulator<i16, L1_SIZE>, old: &[u64; FEATURE_WORDS], new: &[u64; FEATURE_WORDS], feature_count: u32) -> bool {
+        let changes = old.iter().zip(new.iter()).map(|(old, new)| (old ^ new).count_ones()).sum::<u32>();
+
+        if changes >= feature_count {
+            return false;
+        }
+
+        for (word_idx, (&old, &new)) in old.iter().zip(new.iter()).enumerate() {
+            map_bits(new & !old, |bit| accumulator.add(&self.l0.weights()[word_idx * 64 + bit]));
+            map_bits(old & !new, |bit| accumulator.sub(&self.l0.weights()[word_idx * 64 + bit]));
+        }
+
+        true
+    }
+}
+
+fn map_features(board: &ChessBoard, features: &mut [u64; FEATURE_WORDS]) -> u32 {
+    *features = [0; FEATURE_WORDS];
+
+    let mut feature_count = 0;
+    Threats3072::map_inputs(board, |input_index| {
+        features[input_index / 64] |= 1 << (input_index % 64);
+        feature_count += 1;
+    });
+
+    feature_count
+}
+
+#[inline]
+pub(super) fn map_bits<F: FnMut(usize)>(mut word: u64, mut method: F) {
+    while word != 0 {
+        method(word.trailing_zeros() as usize);
+        word &= word - 1;
+    }
+}
@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+
+use chess::{ChessBoard, Side};
+
+use crate::networks::layers::Accumulator;
+
+use super::{accumulator_stack::{map_bits, FEATURE_WORDS}, ValueNetwork, L1_SIZE};
+
+//-----------------------------------------------
+// Threats3072 inputs are side to move relative and mirrored by king file, so an accumulator
+// can only be reused by positions with the same side to move and mirroring. When a position
+// on the selection path can't be updated from the one two plies up, usually because the king
+// crossed the mirror line, it's built from the last accumulator this thread refreshed for the
+// same perspective instead.
+//-----------------------------------------------
+
+#[derive(Clone, Copy)]
+struct RefreshEntry {
+    features: [u64; FEATURE_WORDS],
+    accumulator: Accumulator<i16, L1_SIZE>,
+    valid: bool,
+}
+
+struct RefreshTable {
+    network: *const ValueNetwork,
+    entries: [RefreshEntry; 4],
+}
+
+thread_local! {
+    static REFRESH_TABLE: RefCell<Box<RefreshTable>> = RefCell::new(Box::new(RefreshTable {
+        network: std::ptr::null(),
+        entries: [RefreshEntry {
+            features: [0; FEATURE_WORDS],
+            accumulator: Accumulator::default(),
+            valid: false,
+        }; 4],
+    }));
+}
+
+impl ValueNetwork {
+    //Builds the L0 output for board, given its active features, from the cached accumulator of the same perspective.
+    pub(super) fn refresh_l0(&self, board: &ChessBoard, features: &[u64; FEATURE_WORDS], feature_count: u32) -> Accumulator<i16, L1_SIZE> {
+        REFRESH_TABLE.with(|table| {
+            let mut table = table.borrow_mut();
+
+            //Cached accumulators belong to the network they were built with.
+            if !std::ptr::eq(table.network, self) {
+                table.network = self;
+                table.entries.iter_mut().for_each(|entry| entry.valid = false);
+            }
+
+            let mirrored = board.king_square(board.side()).get_file() > 3;
+            let entry = &mut table.entries[usize::from(board.side() == Side::BLACK) * 2 + usize::from(mirrored)];
+
+            if entry.valid && self.update_l0(&mut entry.accumulator, &entry.features, features, feature_count) {
+                entry.features = *features;
+                return entry.accumulator;
+            }
+
+            entry.accumulator = *self.l0.biases();
+            for (word_idx, &word) in features.iter().enumerate() {
+                map_bits(word, |bit| entry.accumulator.add(&self.l0.weights()[word_idx * 64 + bit]));
+            }
+
+            entry.features = *features;
+            entry.valid = true;
+
+            entry.accumulator
+        })
+    }
+}
@@ -1,11 +1,14 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, thread::{Scope, ScopedJoinHandle}};
 
 use chess::{ChessBoard, ChessPosition, FEN};
 
-use crate::{search_engine::{contempt::Contempt, engine_options::EngineOptions}, search_report_trait::SearchReport};
+use crate::{networks::{free_replaced_networks, load_policy_network, load_value_network}, search_engine::{contempt::Contempt, engine_options::EngineOptions, worker_pool::WorkerPool}, search_report_trait::SearchReport, syzygy::{load_syzygy, probe_root}};
 
 mod bench;
 mod mcts;
+mod search_handle;
+mod search_result;
+mod worker_pool;
 mod search_limits;
 mod search_stats;
 mod tree;
@@ -13,7 +16,9 @@ mod engine_options;
 mod hash_table;
 mod contempt;
 
-pub use search_limits::SearchLimits;
+pub use search_handle::SearchHandle;
+pub use search_limits::{GoCommand, SearchLimits};
+pub use search_result::{LineScore, SearchLine, SearchResult};
 pub use search_stats::SearchStats;
 pub use tree::{Tree, Node, GameState, AtomicWDLScore, WDLScore, PvLine, NodeIndex};
 
@@ -23,8 +28,11 @@ pub struct SearchEngine {
     tree: Tree,
     options: EngineOptions,
     interruption_token: AtomicBool,
+    ponder_token: AtomicBool,
+    searching: AtomicBool,
     game_ply: u16,
-    contempt: Contempt
+    contempt: Contempt,
+    worker_pool: WorkerPool
 }
 
 impl Clone for SearchEngine {
@@ -34,8 +42,11 @@ impl Clone for SearchEngine {
             tree: self.tree.clone(),
             options: self.options.clone(),
             interruption_token: AtomicBool::new(self.interruption_token.load(Ordering::Relaxed)),
+            ponder_token: AtomicBool::new(self.ponder_token.load(Ordering::Relaxed)),
+            searching: AtomicBool::new(false),
             game_ply: self.game_ply,
-            contempt: self.contempt
+            contempt: self.contempt,
+            worker_pool: WorkerPool::new(self.worker_pool.size())
         }
     }
 }
@@ -50,8 +61,11 @@ impl SearchEngine {
             tree: Tree::from_bytes(options.hash() as usize, options.hash_size()),
             options,
             interruption_token: AtomicBool::new(false),
+            ponder_token: AtomicBool::new(false),
+            searching: AtomicBool::new(false),
             game_ply: 0,
-            contempt
+            contempt,
+            worker_pool: WorkerPool::new(0)
         }
     }
 
@@ -75,9 +89,41 @@ impl SearchEngine {
         &self.options
     }
 
-    #[inline]
     pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
-        self.options.set_option(name, value)
+        self.options.set_option(name, value)?;
+
+        //The thread calling search is the first search thread, the pool provides the rest
+        if name.eq_ignore_ascii_case("Threads") {
+            self.worker_pool.resize(self.options.threads() as usize - 1);
+            return Ok(());
+        }
+
+        let result = if name.eq_ignore_ascii_case("EvalFile") || name.eq_ignore_ascii_case("PolicyFile") {
+            let result = if name.eq_ignore_ascii_case("EvalFile") {
+                load_value_network(&self.options.eval_file())
+            } else {
+                load_policy_network(&self.options.policy_file())
+            };
+
+            //The replaced net can go once nothing evaluates with it. Nets are shared by every engine
+            //in the process, so the others have to be idle as well.
+            if !self.is_searching() {
+                unsafe { free_replaced_networks() };
+            }
+
+            result
+        } else if name.eq_ignore_ascii_case("SyzygyPath") {
+            load_syzygy(&self.options.syzygy_path())
+        } else {
+            return Ok(());
+        };
+
+        //The embedded net is back in use (or probing is disabled), so the option has to say so as well.
+        if result.is_err() {
+            let _ = self.options.set_option(name, "");
+        }
+
+        result
     }
 
     #[inline]
@@ -117,20 +163,118 @@ impl SearchEngine {
         self.interruption_token.load(Ordering::Relaxed)
     }
 
-    pub fn search<Display: SearchReport>(&self, search_limits: &SearchLimits) -> SearchStats {
+    pub fn save_tree(&self, path: &str) -> Result<(), String> {
+        self.tree().save(path, self.root_position())
+    }
+
+    //The loaded tree comes with its own root position, the game history before it is lost
+    pub fn load_tree(&mut self, path: &str) -> Result<(), String> {
+        let position = self.tree().load(path)?;
+        self.set_position(&position, 0);
+        Ok(())
+    }
+
+    //The opponent played the predicted move, the ponder search continues under its time limits
+    #[inline]
+    pub fn ponderhit(&self) {
+        self.ponder_token.store(false, Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn is_pondering(&self) -> bool {
+        self.ponder_token.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn is_searching(&self) -> bool {
+        self.searching.load(Ordering::Acquire)
+    }
+
+    //Fails when another search is already running on this engine
+    pub fn search<Display: SearchReport>(&self, search_limits: &SearchLimits, report: &mut Display) -> Result<SearchResult, String> {
+        self.begin_search(search_limits)?;
+        let _guard = SearchGuard(self);
+        Ok(self.run_search(search_limits, report))
+    }
+
+    //Runs the search on a background thread. The engine stays shared with the handle until the search is over.
+    //Fails when another search is already running on this engine.
+    pub fn start_search<Display: SearchReport + 'static>(self: &Arc<Self>, search_limits: SearchLimits, report: Display) -> Result<SearchHandle, String> {
+        //Tokens are set before the thread starts, so an early stop or ponderhit isn't lost
+        self.begin_search(&search_limits)?;
+        Ok(SearchHandle::spawn(Arc::clone(self), search_limits, report))
+    }
+
+    //Runs the search on a thread of the given scope. Like start_search, the tokens are set before the thread starts.
+    //Fails when another search is already running on this engine.
+    pub fn spawn_search<'scope, Display: SearchReport + Send + 'scope>(&'scope self, scope: &'scope Scope<'scope, '_>, search_limits: &'scope SearchLimits, mut report: Display) -> Result<ScopedJoinHandle<'scope, SearchResult>, String> {
+        self.begin_search(search_limits)?;
+        let guard = SearchGuard(self);
+        Ok(scope.spawn(move || {
+            let _guard = guard;
+            self.run_search(search_limits, &mut report)
+        }))
+    }
+
+    //Two searches on one engine would share the tree and the worker pool, so only one may run at a time
+    fn begin_search(&self, search_limits: &SearchLimits) -> Result<(), String> {
+        if self.searching.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            return Err("A search is already running on this engine".to_string());
+        }
+
         self.interruption_token.store(false, Ordering::Relaxed);
+        self.ponder_token.store(search_limits.is_ponder(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    #[inline]
+    fn end_search(&self) {
+        self.searching.store(false, Ordering::Release)
+    }
+
+    fn run_search<Display: SearchReport>(&self, search_limits: &SearchLimits, report: &mut Display) -> SearchResult {
+        self.tree().hash_table().increment_age();
 
         if self.tree().root_node().children_count() == 0 {
-            self.tree().expand_node(self.tree().root_index(), 1.0, self.root_position().board(), self.options());
+            self.tree().expand_node(self.tree().root_index(), 1.0, self.root_position().board(), self.options(), search_limits.mate().is_some());
+        }
+
+        if let Some(moves) = probe_root(self.root_position()) {
+            self.tree().retain_root_children(|mv| moves.contains(&mv));
         }
 
-        Display::search_started(search_limits, self);
+        let mut root_moves = Vec::new();
+        self.tree().root_node().map_children(|child_idx| {
+            let mv = self.tree()[child_idx].mv();
+            if search_limits.search_moves().contains(&mv) {
+                root_moves.push(mv);
+            }
+        });
+
+        self.tree().set_root_moves(&root_moves);
+
+        let carried_visits = self.tree().root_node().visits();
+        if carried_visits > 0 {
+            report.tree_reused(search_limits, carried_visits, self);
+        }
 
-        let result = self.mcts::<Display>(search_limits);
+        report.search_started(search_limits, self);
 
-        Display::search_report(search_limits, &result, self);
-        Display::search_ended(search_limits, &result, self);
+        let search_stats = self.mcts(search_limits, report);
+        let result = SearchResult::new(&search_stats, self);
+
+        report.search_report(search_limits, &search_stats, self);
+        report.search_ended(search_limits, &result, self);
 
         result
     }
 }
+
+//Ends the search when dropped, so a panicking search doesn't leave the engine marked as searching
+struct SearchGuard<'a>(&'a SearchEngine);
+
+impl Drop for SearchGuard<'_> {
+    fn drop(&mut self) {
+        self.0.end_search()
+    }
+}
@@ -74,12 +74,8 @@ impl SearchEngine {
             self.tree().clear();
             self.set_position(&ChessPosition::from(board), 0);
 
-            let result = self.search::<NoReport>(&search_limits);
-            nodes += if self.options().report_iters() {
-                result.iterations()
-            } else {
-                result.cumulative_depth()
-            };
+            //The engine is borrowed mutably, so no other search can be running on it
+            nodes += self.search(&search_limits, &mut NoReport).expect("Bench search failed to start.").nodes();
         }
 
         let duration = timer.elapsed();
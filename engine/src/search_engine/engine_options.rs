@@ -6,13 +6,23 @@ create_options! {
     EngineOptions {
         Options {
             //====== General ======
-            ["Hash"]         hash:          i64   =>  32,  1,  524288;
-            ["Threads"]      threads:       i64   =>  1,   1,  1024;
-            ["MoveOverhead"] move_overhead: i64   =>  25,  0,  2000;
-            ["MultiPV"]      multi_pv:      i64   =>  1,   1,  218;
-            ["UCI_Chess960"] chess960:      bool  =>  false;
-            ["UCI_ShowWDL"]  show_wdl:      bool  =>  false;
-            ["Report_iters"] report_iters:  bool  =>  false;
+            ["Hash"]           hash:            i64     =>  32,  1,  524288;
+            ["Threads"]        threads:         i64     =>  1,   1,  1024;
+            ["MoveOverhead"]   move_overhead:   i64     =>  25,  0,  2000;
+            ["MultiPV"]        multi_pv:        i64     =>  1,   1,  218;
+            ["UCI_Chess960"]   chess960:        bool    =>  false;
+            ["UCI_ShowWDL"]    show_wdl:        bool    =>  false;
+            ["Report_iters"]   report_iters:    bool    =>  false;
+            ["Ponder"]         ponder:          bool    =>  false;
+            //Children are shared only by positions reached with a zeroing move, where the repetition history starts over.
+            //Quiet transpositions like 1.Nf3 Nf6 2.Nc3 and 1.Nc3 Nf6 2.Nf3 keep separate subtrees.
+            ["Transpositions"] transpositions:  bool    =>  false;
+            //Same tree for the same position, limits and thread count. Only node, depth and mate limits stop reproducibly,
+            //threads wait for each other every iteration and skip the lock on first visits of a child.
+            ["Deterministic"]  deterministic:   bool    =>  false;
+            ["EvalFile"]       eval_file:       String  =>  String::new();
+            ["PolicyFile"]     policy_file:     String  =>  String::new();
+            ["SyzygyPath"]     syzygy_path:     String  =>  String::new();
 
             //======== EAS ========
             ["Contempt"]  contempt:   i64  =>  1000,  -10000,  10000;
@@ -1,54 +1,166 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
 
 use chess::ZobristKey;
 
-use crate::{AtomicWDLScore, WDLScore};
+use crate::{AtomicWDLScore, NodeIndex, WDLScore};
+
+const BUCKET_SIZE: usize = 4;
+const HASHFULL_SAMPLE: usize = 1000;
 
 #[derive(Debug, Default)]
 pub struct TableEntry {
     score: AtomicWDLScore,
     hash: AtomicU64,
+    visits: AtomicU32,
+    age: AtomicU8,
+    //Tree generation in the high half, children index in the low half
+    children: AtomicU64,
 }
 
 impl Clone for TableEntry {
     fn clone(&self) -> Self {
-        Self { 
-            score: self.score.clone(), 
+        Self {
+            score: self.score.clone(),
             hash: AtomicU64::new(self.hash.load(Ordering::Relaxed)),
+            visits: AtomicU32::new(self.visits.load(Ordering::Relaxed)),
+            age: AtomicU8::new(self.age.load(Ordering::Relaxed)),
+            children: AtomicU64::new(self.children.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl TableEntry {
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.visits.load(Ordering::Relaxed) == 0
+    }
+
+    fn store(&self, key: u64, score: WDLScore, visits: u32, age: u8) {
+        if self.hash.load(Ordering::Relaxed) != key {
+            self.children.store(0, Ordering::Relaxed);
+        }
+
+        self.score.store(score);
+        self.hash.store(key, Ordering::Relaxed);
+        self.visits.store(visits, Ordering::Relaxed);
+        self.age.store(age, Ordering::Relaxed);
+    }
+
+    fn clear(&self) {
+        self.score.clear();
+        self.hash.store(0, Ordering::Relaxed);
+        self.visits.store(0, Ordering::Relaxed);
+        self.age.store(0, Ordering::Relaxed);
+        self.children.store(0, Ordering::Relaxed);
+    }
+}
+
+type Bucket = [TableEntry; BUCKET_SIZE];
+
+#[derive(Debug)]
+pub struct HashTable {
+    buckets: Vec<Bucket>,
+    age: AtomicU8,
+}
+
+impl Clone for HashTable {
+    fn clone(&self) -> Self {
+        Self {
+            buckets: self.buckets.clone(),
+            age: AtomicU8::new(self.age()),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct HashTable(Vec<TableEntry>);
 impl HashTable {
     pub fn new(bytes: usize) -> Self {
-        let size = bytes / std::mem::size_of::<TableEntry>();
-        Self(vec![TableEntry::default(); size])
+        let size = (bytes / std::mem::size_of::<Bucket>()).max(1);
+        Self {
+            buckets: vec![Bucket::default(); size],
+            age: AtomicU8::new(0),
+        }
     }
 
     pub fn clear(&self) {
-        for entry in &self.0 {
-            entry.score.clear();
-            entry.hash.store(0, Ordering::Relaxed);
+        for entry in self.buckets.iter().flatten() {
+            entry.clear();
         }
+
+        self.age.store(0, Ordering::Relaxed);
     }
 
-    pub fn get(&self, key: ZobristKey) -> Option<WDLScore> {
-        let idx = u64::from(key) % self.0.len() as u64;
-        let entry = &self.0[idx as usize];
+    #[inline]
+    pub fn age(&self) -> u8 {
+        self.age.load(Ordering::Relaxed)
+    }
 
-        if entry.hash.load(Ordering::Relaxed) == u64::from(key) {
-            Some(entry.score.get_score())
-        } else {
-            None
+    //Entries from earlier searches stay readable, but are replaced first
+    #[inline]
+    pub fn increment_age(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self, key: ZobristKey) -> Option<(WDLScore, u32)> {
+        let key = u64::from(key);
+
+        self.find(key).map(|entry| (entry.score.get_score(), entry.visits.load(Ordering::Relaxed)))
+    }
+
+    //The same position keeps its best searched score, otherwise the least visited entry of an older search is replaced
+    pub fn push(&self, key: ZobristKey, score: WDLScore, visits: u32) {
+        let key = u64::from(key);
+        let age = self.age();
+        let bucket = self.bucket(key);
+
+        if let Some(entry) = self.find(key) {
+            if entry.age.load(Ordering::Relaxed) != age || visits >= entry.visits.load(Ordering::Relaxed) {
+                entry.store(key, score, visits, age);
+            }
+
+            return;
+        }
+
+        let victim = bucket.iter().min_by_key(|entry| {
+            let current = !entry.is_empty() && entry.age.load(Ordering::Relaxed) == age;
+            (current, entry.visits.load(Ordering::Relaxed))
+        }).unwrap();
+
+        victim.store(key, score, visits, age);
+    }
+
+    //Children block expanded for the position in the given tree generation
+    pub fn children(&self, key: ZobristKey, generation: u32) -> Option<NodeIndex> {
+        let key = u64::from(key);
+        let entry = self.find(key)?;
+
+        let children = entry.children.load(Ordering::Relaxed);
+        (children != 0 && (children >> 32) as u32 == generation).then(|| NodeIndex::from(children as u32))
+    }
+
+    //Only positions that already have an entry remember their children
+    pub fn set_children(&self, key: ZobristKey, children_idx: NodeIndex, generation: u32) {
+        if let Some(entry) = self.find(u64::from(key)) {
+            entry.children.store((u64::from(generation) << 32) | u64::from(u32::from(children_idx)), Ordering::Relaxed);
         }
     }
 
-    pub fn push(&self, key: ZobristKey, score: WDLScore) {
-        let idx = u64::from(key) % self.0.len() as u64;
+    //Permille of sampled entries written during the current search
+    pub fn hashfull(&self) -> usize {
+        let age = self.age();
+        let sample = self.buckets.iter().flatten().take(HASHFULL_SAMPLE);
+        let sample_size = sample.clone().count();
 
-        self.0[idx as usize].score.store(score);
-        self.0[idx as usize].hash.store(u64::from(key), Ordering::Relaxed);
+        let used = sample.filter(|entry| !entry.is_empty() && entry.age.load(Ordering::Relaxed) == age).count();
+        used * 1000 / sample_size
     }
-}
\ No newline at end of file
+
+    #[inline]
+    fn find(&self, key: u64) -> Option<&TableEntry> {
+        self.bucket(key).iter().find(|entry| !entry.is_empty() && entry.hash.load(Ordering::Relaxed) == key)
+    }
+
+    #[inline]
+    fn bucket(&self, key: u64) -> &Bucket {
+        &self.buckets[(key % self.buckets.len() as u64) as usize]
+    }
+}
@@ -1,16 +1,20 @@
-use std::{thread, time::Instant};
+use std::time::Instant;
 
 use chess::Move;
 
 use crate::{
+    networks::AccumulatorStack,
     search_engine::{search_limits::TimeManager, SearchLimits, SearchStats},
-    SearchEngine, SearchReport,
+    GameState, SearchEngine, SearchReport,
 };
 
+use lockstep::{Lockstep, Turn};
+
 mod iteration;
+mod lockstep;
 
 impl SearchEngine {
-    pub(super) fn mcts<Display: SearchReport>(&self, search_limits: &SearchLimits) -> SearchStats {
+    pub(super) fn mcts<Display: SearchReport>(&self, search_limits: &SearchLimits, report: &mut Display) -> SearchStats {
         let castle_mask = self
             .root_position()
             .board()
@@ -24,27 +28,27 @@ impl SearchEngine {
 
         let mut last_best_move = None;
         let mut best_move_changes = 0;
+        let mut proven = false;
+        let mut start_time = 0;
 
         loop 
         {
             let mut time_manager = search_limits.time_manager();
+            time_manager.set_start_time(start_time);
 
-            thread::scope(|s| {
-                s.spawn(|| {
-                    self.main_loop::<Display>(&search_stats, &search_limits, &mut time_manager, &castle_mask, &mut search_report_timer, &mut max_avg_depth, &mut last_best_move, &mut best_move_changes);
-                });
+            let lockstep = self.options().deterministic().then(|| Lockstep::new(self.worker_pool.size() + 1));
 
-                for _ in 0..(self.options().threads() - 1) {
-                    s.spawn(|| {
-                        self.worker_loop(&search_stats, &search_limits, &castle_mask)
-                    });
-                }
+            self.worker_pool.run(&|| {
+                self.worker_loop(&search_stats, search_limits, &castle_mask, lockstep.as_ref());
+            }, || {
+                self.main_loop(&search_stats, search_limits, report, &mut time_manager, &castle_mask, &mut search_report_timer, &mut max_avg_depth, &mut last_best_move, &mut best_move_changes, &mut proven, lockstep.as_ref());
             });
 
             if self.is_search_interrupted() {
                 break;
             }
 
+            start_time = time_manager.start_time();
             self.tree().swap_half();
         }
 
@@ -55,18 +59,25 @@ impl SearchEngine {
         &self,
         search_stats: &SearchStats,
         search_limits: &SearchLimits,
+        report: &mut Display,
         time_manager: &mut TimeManager,
         castle_mask: &[u8; 64],
         search_report_timer: &mut Instant,
         max_avg_depth: &mut u64,
         last_best_move: &mut Option<Move>,
-        best_move_changes: &mut usize
+        best_move_changes: &mut usize,
+        proven: &mut bool,
+        lockstep: Option<&Lockstep>
     ) -> Option<()> {
-        while !self.is_search_interrupted() {
-            self.search_step(search_stats, search_limits, castle_mask)?;
+        let mut turn = lockstep.map(Lockstep::turn);
+        let mut accumulators = AccumulatorStack::default();
+
+        //In lockstep the loop is left through search_step, once the whole round has stopped
+        while turn.is_some() || !self.is_search_interrupted() {
+            self.search_step(search_stats, search_limits, castle_mask, &mut accumulators, turn.as_mut())?;
 
-            if search_stats.avg_depth() > *max_avg_depth || search_report_timer.elapsed().as_secs_f64() > (1.0 / Display::refresh_rate_per_second()) {
-                Display::search_report(search_limits, search_stats, self);
+            if search_stats.avg_depth() > *max_avg_depth || search_report_timer.elapsed().as_secs_f64() > (1.0 / report.refresh_rate_per_second()) {
+                report.search_report(search_limits, search_stats, self);
                 *search_report_timer = Instant::now();
                 *max_avg_depth = search_stats.avg_depth().max(*max_avg_depth);
             }
@@ -79,15 +90,30 @@ impl SearchEngine {
                 }
             }
 
+            if *last_best_move != Some(best_move) {
+                report.best_move_changed(search_limits, search_stats, best_move, self);
+            }
+
             *last_best_move = Some(best_move);
 
+            let root_state = self.tree().root_node().state();
+            if !*proven && root_state != GameState::Ongoing {
+                report.proof_found(search_limits, search_stats, root_state, self);
+                *proven = true;
+            }
+
+            if self.is_pondering() {
+                time_manager.set_start_time(search_stats.time_passesd_ms());
+                continue;
+            }
+
             if search_stats.iterations() % 128 != 0 {
                 continue;
             }
 
             if time_manager.hard_limit_reached(search_stats) {
                 self.interrupt_search();
-                break;
+                continue;
             }
 
             if search_stats.iterations() % 4096 != 0 {
@@ -96,7 +122,7 @@ impl SearchEngine {
 
             if time_manager.soft_limit_reached(search_stats, self.tree(), self.options(), *best_move_changes) {
                 self.interrupt_search();
-                break;
+                continue;
             }
 
             if search_stats.iterations() % 16384 != 0 {
@@ -114,9 +140,13 @@ impl SearchEngine {
         search_stats: &SearchStats,
         search_limits: &SearchLimits,
         castle_mask: &[u8; 64],
+        lockstep: Option<&Lockstep>,
     ) -> Option<()> {
-        while !self.is_search_interrupted() {
-            self.search_step(search_stats, search_limits, castle_mask)?;
+        let mut turn = lockstep.map(Lockstep::turn);
+        let mut accumulators = AccumulatorStack::default();
+
+        while turn.is_some() || !self.is_search_interrupted() {
+            self.search_step(search_stats, search_limits, castle_mask, &mut accumulators, turn.as_mut())?;
         }
 
         Some(())
@@ -127,18 +157,41 @@ impl SearchEngine {
         search_stats: &SearchStats,
         search_limits: &SearchLimits,
         castle_mask: &[u8; 64],
+        accumulators: &mut AccumulatorStack,
+        mut turn: Option<&mut Turn>,
     ) -> Option<()> {
+        if let Some(turn) = &turn {
+            if !turn.begin(|| self.is_search_interrupted()) {
+                return None;
+            }
+        }
+
         let mut depth = 0.0;
         let mut position = *self.root_position();
+        accumulators.reset(position.board());
 
-        self.perform_iteration::<true>(self.tree().root_index(), &mut position, &mut depth, castle_mask)?;
+        let score = self.perform_iteration::<true>(self.tree().root_index(), &mut position, &mut depth, castle_mask, search_limits, accumulators, turn.as_deref());
+
+        if score.is_none() {
+            //The tree is full, lockstep threads finish the round together before the halves are swapped
+            if let Some(turn) = turn {
+                turn.abort();
+                return Some(());
+            }
+
+            return None;
+        }
 
         search_stats.add_iteration(depth as u64);
 
-        if search_limits.is_limit_reached(search_stats) {
+        if !self.is_pondering() && search_limits.is_limit_reached(search_stats, self.tree()) {
             self.interrupt_search();
         }
 
+        if let Some(turn) = &mut turn {
+            turn.finish();
+        }
+
         Some(())
     }
 }
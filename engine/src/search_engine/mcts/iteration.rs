@@ -1,6 +1,6 @@
 use chess::ChessPosition;
 
-use crate::{search_engine::tree::NodeIndex, SearchEngine, WDLScore};
+use crate::{networks::AccumulatorStack, search_engine::{mcts::lockstep::Turn, tree::NodeIndex}, SearchEngine, SearchLimits, WDLScore};
 
 mod select;
 mod simulate;
@@ -13,38 +13,63 @@ impl SearchEngine {
         position: &mut ChessPosition,
         depth: &mut f64,
         castle_mask: &[u8; 64],
+        search_limits: &SearchLimits,
+        accumulators: &mut AccumulatorStack,
+        turn: Option<&Turn>,
     ) -> Option<WDLScore> { 
-        let hash = position.board().hash();
+        let key = position.repetition_key();
         let node = &self.tree()[node_idx];
 
         let mut selected_child_idx = None;
 
         let score = if !ROOT && (node.is_terminal() || node.visits() == 0) {
-            self.simulate(node_idx, position, *depth)
+            if let Some(turn) = turn {
+                turn.leaf_reached();
+            }
+
+            let score = self.simulate(node_idx, position, *depth, accumulators);
+
+            if let Some(turn) = turn {
+                turn.leaf_evaluated();
+            }
+
+            score
         } else {
             *depth += 1.0;
 
+            //Zeroing moves start a new history, so the subtree is the same for every path to the position
+            let shared = !ROOT && self.options().transpositions() && position.board().half_moves() == 0;
+            if shared {
+                self.tree().link_children(node_idx, key, position.board());
+            }
+
             if node.children_count() == 0 {
-                self.tree().expand_node(node_idx, *depth, position.board(), self.options())?
+                self.tree().expand_node(node_idx, *depth, position.board(), self.options(), search_limits.mate().is_some())?
             }
 
             self.tree().update_node(node_idx)?;
 
-            let new_idx = self.select(node_idx, *depth);
+            if shared {
+                self.tree().share_children(node_idx, key);
+            }
+
+            let new_idx = self.select(node_idx, *depth, if ROOT { search_limits.search_moves() } else { &[] });
 
             selected_child_idx = Some(new_idx);
 
             position.make_move(self.tree()[new_idx].mv(), castle_mask);
+            accumulators.push(position.board());
 
             self.tree().inc_threads(new_idx, 1);
 
-            let lock = if self.tree()[new_idx].visits() == 0 {
+            //Lockstep threads wait for each other within a round, holding the lock across it would deadlock them
+            let lock = if turn.is_none() && self.tree()[new_idx].visits() == 0 {
                 Some(node.children_index_mut())
             } else {
                 None
             };
 
-            let score = self.perform_iteration::<false>(new_idx, position, depth, castle_mask);
+            let score = self.perform_iteration::<false>(new_idx, position, depth, castle_mask, search_limits, accumulators, turn);
 
             drop(lock);
 
@@ -53,7 +78,7 @@ impl SearchEngine {
             score?
         }.reversed();
 
-        self.backpropagate(node_idx, selected_child_idx, score, hash);
+        self.backpropagate(node_idx, selected_child_idx, score, key);
 
         Some(score)
     }
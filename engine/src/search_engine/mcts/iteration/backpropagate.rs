@@ -6,7 +6,9 @@ impl SearchEngine {
     pub(super) fn backpropagate(&self, node_idx: NodeIndex, child_idx: Option<NodeIndex>, score: WDLScore, key: ZobristKey) {
         self.tree().add_visit(node_idx, score);
         backprop_state(self.tree(), node_idx, child_idx);
-        self.tree().hash_table().push(key, score.reversed());
+
+        let node = &self.tree()[node_idx];
+        self.tree().hash_table().push(key, node.score().reversed(), node.visits());
     }
 }
 
@@ -17,19 +19,30 @@ fn backprop_state(tree: &Tree, node_idx: NodeIndex, child_idx: Option<NodeIndex>
         GameState::Loss(len) => {
             tree.set_state(node_idx, GameState::Win(len + 1));
         },
-        GameState::Win(len) => {
-            let mut proven_loss = true;
-            let mut proven_loss_length = len;
+        GameState::TablebaseLoss => {
+            tree.set_state(node_idx, GameState::TablebaseWin);
+        },
+        GameState::Win(_) | GameState::TablebaseWin | GameState::Draw => {
+            let mut proven = true;
+            let mut proven_loss_length = 0;
+            let mut tablebase_loss = false;
+            let mut draw = false;
 
+            //Once every child is proven, the best of them decides: a draw beats any loss
             tree[node_idx].map_children(|child_idx| {
-                if let GameState::Win(x) = tree[child_idx].state() {
-                    proven_loss_length = x.max(proven_loss_length);
-                } else {
-                    proven_loss = false;
+                match tree[child_idx].state() {
+                    GameState::Win(x) => proven_loss_length = x.max(proven_loss_length),
+                    GameState::TablebaseWin => tablebase_loss = true,
+                    GameState::Draw => draw = true,
+                    _ => proven = false,
                 }
             });
 
-            if proven_loss {
+            if proven && draw {
+                tree.set_state(node_idx, GameState::Draw);
+            } else if proven && tablebase_loss {
+                tree.set_state(node_idx, GameState::TablebaseLoss);
+            } else if proven {
                 tree.set_state(node_idx, GameState::Loss(proven_loss_length + 1));
             }
         },
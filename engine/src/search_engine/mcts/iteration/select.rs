@@ -1,7 +1,10 @@
+use chess::Move;
+
 use crate::{search_engine::{engine_options::EngineOptions, tree::NodeIndex}, Node, SearchEngine, WDLScore};
 
 impl SearchEngine {
-    pub(super) fn select(&self, node_idx: NodeIndex, depth: f64) -> NodeIndex {
+    //Allowed moves restrict the root to searchmoves, they come from the search limits so no lock is taken here
+    pub(super) fn select(&self, node_idx: NodeIndex, depth: f64, allowed: &[Move]) -> NodeIndex {
         let parent_node = &self.tree()[node_idx];
 
         let cpuct = get_cpuct(&self.options(), &parent_node, depth);
@@ -9,7 +12,7 @@ impl SearchEngine {
 
         let expl = cpuct * exploration_scale;
 
-        self.tree().select_child_by_key(node_idx, |child_node| {
+        self.tree().select_child_by_key(node_idx, allowed, |child_node| {
             let score = get_score(&parent_node.score(), child_node, child_node.visits()).single_with_score(if depth as i64 % 2 == 0 {
                 0.5
             } else {
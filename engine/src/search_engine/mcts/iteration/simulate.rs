@@ -1,24 +1,40 @@
 use chess::ChessPosition;
 
-use crate::{search_engine::{contempt::Contempt, engine_options::EngineOptions, tree::NodeIndex}, GameState, SearchEngine, ValueNetwork, WDLScore};
+use crate::{search_engine::{contempt::Contempt, engine_options::EngineOptions, tree::NodeIndex}, networks::{value_network, AccumulatorStack}, syzygy::{probe_wdl, TablebaseWDL}, GameState, SearchEngine, WDLScore};
+
+//Hash entries with at least this many visits are used without evaluating the network
+const HASH_TRUST_VISITS: u32 = 8;
 
 impl SearchEngine {
-    pub(super) fn simulate(&self, node_idx: NodeIndex, position: &ChessPosition, depth: f64) -> WDLScore {
+    pub(super) fn simulate(&self, node_idx: NodeIndex, position: &ChessPosition, depth: f64, accumulators: &mut AccumulatorStack) -> WDLScore {
         if self.tree()[node_idx].visits() == 0 {
             let state = get_node_state(position, self.root_position());
             self.tree().set_state(node_idx, state);
         }
 
+        let state = self.tree()[node_idx].state();
         let is_stm = self.root_position().board().side() == position.board().side();
 
-        if self.tree[node_idx].state() == GameState::Ongoing {
-            if let Some(entry) = self.tree().hash_table().get(position.board().hash()) {
-                entry
-            } else {
-                get_position_score(position, self.tree()[node_idx].state(), self.contempt(), self.options(), is_stm, depth)
-            }
+        if state != GameState::Ongoing {
+            return get_position_score(position, state, self.contempt(), self.options(), is_stm, depth, accumulators);
+        }
+
+        //A transposition searched elsewhere replaces the network once it has enough visits, below that both are blended
+        let entry = self.tree().hash_table().get(position.repetition_key());
+        if let Some((entry_score, _)) = entry.filter(|&(_, visits)| visits >= HASH_TRUST_VISITS) {
+            return entry_score;
+        }
+
+        let score = get_position_score(position, state, self.contempt(), self.options(), is_stm, depth, accumulators);
+
+        if let Some((entry_score, visits)) = entry {
+            let weight = f64::from(visits) / f64::from(visits + 1);
+            WDLScore::new(
+                entry_score.win_chance() * weight + score.win_chance() * (1.0 - weight),
+                entry_score.draw_chance() * weight + score.draw_chance() * (1.0 - weight)
+            )
         } else {
-            get_position_score(position, self.tree()[node_idx].state(), self.contempt(), self.options(), is_stm, depth)
+            score
         }
     }
 }
@@ -35,6 +51,14 @@ fn get_node_state(position: &ChessPosition, root_position: &ChessPosition) -> Ga
         }
     } else if is_draw(position, root_position) {
         GameState::Draw
+    } else if position.board().half_moves() == 0 {
+        //Probing only right after zeroing moves keeps the result exact under the 50-move rule
+        match probe_wdl(position.board()) {
+            Some(TablebaseWDL::Win) => GameState::TablebaseWin,
+            Some(TablebaseWDL::Loss) => GameState::TablebaseLoss,
+            Some(_) => GameState::Draw,
+            None => GameState::Ongoing,
+        }
     } else {
         GameState::Ongoing
     }
@@ -56,12 +80,12 @@ fn is_draw(position: &ChessPosition, root_position: &ChessPosition) -> bool {
     false
 }
 
-fn get_position_score(position: &ChessPosition, node_state: GameState, contempt: &Contempt, options: &EngineOptions, is_stm: bool, depth: f64) -> WDLScore {
+fn get_position_score(position: &ChessPosition, node_state: GameState, contempt: &Contempt, options: &EngineOptions, is_stm: bool, depth: f64, accumulators: &mut AccumulatorStack) -> WDLScore {
     let mut score = match node_state {
         GameState::Draw => WDLScore::DRAW,
-        GameState::Loss(_) => WDLScore::LOSE,
-        GameState::Win(_) => WDLScore::WIN,
-        _ => ValueNetwork.forward(position.board())
+        GameState::Loss(_) | GameState::TablebaseLoss => WDLScore::LOSE,
+        GameState::Win(_) | GameState::TablebaseWin => WDLScore::WIN,
+        _ => value_network().forward_stack(accumulators)
     };
 
     score.apply_50mr(position.board().half_moves(), depth, options);
@@ -0,0 +1,122 @@
+use std::sync::{atomic::{AtomicU64, Ordering}, Condvar, Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug, Default)]
+struct LockstepState {
+    descended: u64,
+    evaluated: u64,
+    finished: u64,
+    aborted: bool,
+    stopped: bool,
+}
+
+//Runs the iterations of all search threads in rounds, one iteration per thread and round. Within a round
+//threads descend the tree one after another in slot order, evaluate their leaves in parallel and
+//backpropagate again in slot order, so the tree only depends on the number of threads.
+#[derive(Debug)]
+pub struct Lockstep {
+    threads: u64,
+    slots: AtomicU64,
+    state: Mutex<LockstepState>,
+    condvar: Condvar,
+}
+
+impl Lockstep {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads as u64,
+            slots: AtomicU64::new(0),
+            state: Mutex::new(LockstepState::default()),
+            condvar: Condvar::new(),
+        }
+    }
+
+    //Which thread takes which slot does not matter, only that every slot is taken once
+    pub fn turn(&self) -> Turn<'_> {
+        Turn {
+            lockstep: self,
+            slot: self.slots.fetch_add(1, Ordering::Relaxed),
+            round: 0,
+        }
+    }
+
+    fn wait_until(&self, condition: impl Fn(&LockstepState) -> bool) -> MutexGuard<'_, LockstepState> {
+        let state = self.state.lock().unwrap();
+        self.condvar.wait_while(state, |state| !condition(state)).unwrap()
+    }
+
+    fn update(&self, update: impl FnOnce(&mut LockstepState)) {
+        update(&mut self.state.lock().unwrap());
+        self.condvar.notify_all();
+    }
+}
+
+#[derive(Debug)]
+pub struct Turn<'a> {
+    lockstep: &'a Lockstep,
+    slot: u64,
+    round: u64,
+}
+
+impl Turn<'_> {
+    #[inline]
+    fn round_start(&self) -> u64 {
+        self.round * self.lockstep.threads
+    }
+
+    #[inline]
+    fn ticket(&self) -> u64 {
+        self.round_start() + self.slot
+    }
+
+    //Waits until this thread may descend. The first slot decides whether another round is played,
+    //so every thread stops after the same round and none is left waiting for the others.
+    pub fn begin(&self, should_stop: impl FnOnce() -> bool) -> bool {
+        let (ticket, round_start) = (self.ticket(), self.round_start());
+        let mut state = self.lockstep.wait_until(|state| state.stopped || (state.descended == ticket && state.finished >= round_start));
+
+        if !state.stopped && self.slot == 0 && (state.aborted || should_stop()) {
+            state.stopped = true;
+            drop(state);
+            self.lockstep.condvar.notify_all();
+            return false;
+        }
+
+        !state.stopped
+    }
+
+    //Called once the descent reached its leaf, hands the tree over to the next slot
+    pub fn leaf_reached(&self) {
+        self.lockstep.update(|state| state.descended += 1);
+    }
+
+    //Called once the leaf is evaluated, returns when it is this thread's turn to backpropagate
+    pub fn leaf_evaluated(&self) {
+        self.lockstep.update(|state| state.evaluated += 1);
+
+        let (ticket, round_end) = (self.ticket(), self.round_start() + self.lockstep.threads);
+        drop(self.lockstep.wait_until(|state| state.stopped || (state.evaluated >= round_end && state.finished == ticket)));
+    }
+
+    //The tree is full, the round is still completed and the search stops before the next one
+    pub fn abort(&mut self) {
+        self.lockstep.update(|state| state.aborted = true);
+        self.leaf_reached();
+        self.leaf_evaluated();
+        self.finish();
+    }
+
+    pub fn finish(&mut self) {
+        self.lockstep.update(|state| state.finished += 1);
+        self.round += 1;
+    }
+}
+
+//A thread unwinding mid-round never takes its next step, so the round is stopped to release the others
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.lockstep.state.lock().unwrap_or_else(PoisonError::into_inner).stopped = true;
+            self.lockstep.condvar.notify_all();
+        }
+    }
+}
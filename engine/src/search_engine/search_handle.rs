@@ -0,0 +1,60 @@
+use std::{sync::Arc, thread::{self, JoinHandle}};
+
+use crate::{PvLine, SearchEngine, SearchLimits, SearchReport, SearchResult};
+
+//Search running on a background thread. Dropping the handle stops the search and waits for its threads to finish.
+pub struct SearchHandle {
+    search_engine: Arc<SearchEngine>,
+    thread: Option<JoinHandle<SearchResult>>,
+}
+
+impl SearchHandle {
+    pub(super) fn spawn<Display: SearchReport + 'static>(search_engine: Arc<SearchEngine>, search_limits: SearchLimits, mut report: Display) -> Self {
+        let engine = Arc::clone(&search_engine);
+        let thread = thread::spawn(move || engine.run_search(&search_limits, &mut report));
+
+        Self {
+            search_engine,
+            thread: Some(thread),
+        }
+    }
+
+    #[inline]
+    pub fn stop(&self) {
+        self.search_engine.interrupt_search()
+    }
+
+    #[inline]
+    pub fn ponderhit(&self) {
+        self.search_engine.ponderhit()
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    //Current best line, its score is the WDL of the first move
+    pub fn peek(&self) -> PvLine {
+        let draw_score = self.search_engine.options().draw_score() as f64 / 100.0;
+        self.search_engine.tree().get_best_pv(0, draw_score)
+    }
+
+    //Blocks until the search ends by its limits or by stop
+    pub fn wait(mut self) -> SearchResult {
+        let thread = self.thread.take().unwrap();
+        let result = thread.join();
+        self.search_engine.end_search();
+        result.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+}
+
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.stop();
+            let _ = thread.join();
+            self.search_engine.end_search();
+        }
+    }
+}
@@ -1,14 +1,21 @@
-use crate::{search_engine::{engine_options::EngineOptions, SearchStats}};
+use chess::Move;
 
+use crate::{search_engine::{engine_options::EngineOptions, SearchStats}, GameState, Tree};
+
+mod go_command;
 mod time_manager;
 
+pub use go_command::GoCommand;
 pub use time_manager::TimeManager;
 
 #[derive(Debug, Default)]
 pub struct SearchLimits {
     depth: Option<u64>,
     iters: Option<u64>,
+    mate: Option<u64>,
+    search_moves: Vec<Move>,
     infinite: bool,
+    ponder: bool,
     time_manager: TimeManager
 }
 
@@ -21,6 +28,26 @@ impl SearchLimits {
         self.iters = iters
     }
     
+    //Stops the search once the root is a proven mate in at most this many moves
+    pub fn set_mate(&mut self, mate: Option<u64>) {
+        self.mate = mate
+    }
+
+    #[inline]
+    pub fn mate(&self) -> Option<u64> {
+        self.mate
+    }
+
+    //Restricts the root to these moves, empty means every move
+    pub fn set_search_moves(&mut self, search_moves: Vec<Move>) {
+        self.search_moves = search_moves
+    }
+
+    #[inline]
+    pub fn search_moves(&self) -> &[Move] {
+        &self.search_moves
+    }
+
     pub fn set_infinite(&mut self, infinite: bool) {
         self.infinite = infinite
     }
@@ -29,11 +56,21 @@ impl SearchLimits {
         self.infinite
     }
 
+    //Search the predicted position without time limits until ponderhit
+    pub fn set_ponder(&mut self, ponder: bool) {
+        self.ponder = ponder
+    }
+
+    #[inline]
+    pub fn is_ponder(&self) -> bool {
+        self.ponder
+    }
+
     pub fn time_manager(&self) -> TimeManager {
         self.time_manager
     }
  
-    pub fn is_limit_reached(&self, search_stats: &SearchStats) -> bool {
+    pub fn is_limit_reached(&self, search_stats: &SearchStats, tree: &Tree) -> bool {
         if self.infinite {
             return false;
         }
@@ -50,6 +87,12 @@ impl SearchLimits {
             }
         }
 
+        if let (Some(mate), GameState::Win(len)) = (self.mate, tree.root_node().state()) {
+            if u64::from(len).div_ceil(2) <= mate {
+                return true;
+            }
+        }
+
         false
     }
 
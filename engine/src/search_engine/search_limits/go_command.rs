@@ -0,0 +1,177 @@
+use std::{fmt::{Display, Formatter, Result}, str::FromStr};
+
+const KEYWORDS: [&str; 12] = ["searchmoves", "ponder", "wtime", "btime", "winc", "binc", "movestogo", "depth", "nodes", "mate", "movetime", "infinite"];
+
+//Arguments of the UCI go command. Moves are kept as text, only the position can tell if they are legal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoCommand {
+    search_moves: Vec<String>,
+    ponder: bool,
+    wtime: Option<u128>,
+    btime: Option<u128>,
+    winc: Option<u128>,
+    binc: Option<u128>,
+    moves_to_go: Option<u128>,
+    depth: Option<u64>,
+    nodes: Option<u64>,
+    mate: Option<u64>,
+    move_time: Option<u128>,
+    infinite: bool,
+}
+
+impl GoCommand {
+    //Parses everything after "go". Unknown tokens and bad values are skipped and described in the returned diagnostics.
+    pub fn parse(args: &[String]) -> (Self, Vec<String>) {
+        let mut command = Self::default();
+        let mut diagnostics = Vec::new();
+
+        let mut idx = 0;
+        while idx < args.len() {
+            let token = args[idx].as_str();
+            idx += 1;
+
+            match token {
+                "searchmoves" => {
+                    while idx < args.len() && !KEYWORDS.contains(&args[idx].as_str()) {
+                        command.search_moves.push(args[idx].clone());
+                        idx += 1;
+                    }
+                }
+                "ponder" => command.ponder = true,
+                "infinite" => command.infinite = true,
+                "wtime" => command.wtime = parse_time(token, args, &mut idx, &mut diagnostics),
+                "btime" => command.btime = parse_time(token, args, &mut idx, &mut diagnostics),
+                "winc" => command.winc = parse_time(token, args, &mut idx, &mut diagnostics),
+                "binc" => command.binc = parse_time(token, args, &mut idx, &mut diagnostics),
+                "movetime" => command.move_time = parse_time(token, args, &mut idx, &mut diagnostics),
+                "movestogo" => command.moves_to_go = parse_value(token, args, &mut idx, &mut diagnostics),
+                "depth" => command.depth = parse_value(token, args, &mut idx, &mut diagnostics),
+                "nodes" => command.nodes = parse_value(token, args, &mut idx, &mut diagnostics),
+                "mate" => command.mate = parse_value(token, args, &mut idx, &mut diagnostics),
+                _ => diagnostics.push(format!("Unknown go parameter '{token}'")),
+            }
+        }
+
+        (command, diagnostics)
+    }
+
+    #[inline]
+    pub fn search_moves(&self) -> &[String] {
+        &self.search_moves
+    }
+
+    #[inline]
+    pub fn ponder(&self) -> bool {
+        self.ponder
+    }
+
+    #[inline]
+    pub fn wtime(&self) -> Option<u128> {
+        self.wtime
+    }
+
+    #[inline]
+    pub fn btime(&self) -> Option<u128> {
+        self.btime
+    }
+
+    #[inline]
+    pub fn winc(&self) -> Option<u128> {
+        self.winc
+    }
+
+    #[inline]
+    pub fn binc(&self) -> Option<u128> {
+        self.binc
+    }
+
+    #[inline]
+    pub fn moves_to_go(&self) -> Option<u128> {
+        self.moves_to_go
+    }
+
+    #[inline]
+    pub fn depth(&self) -> Option<u64> {
+        self.depth
+    }
+
+    //MCTS iterations, not nodes in the alpha-beta sense
+    #[inline]
+    pub fn nodes(&self) -> Option<u64> {
+        self.nodes
+    }
+
+    #[inline]
+    pub fn mate(&self) -> Option<u64> {
+        self.mate
+    }
+
+    #[inline]
+    pub fn move_time(&self) -> Option<u128> {
+        self.move_time
+    }
+
+    #[inline]
+    pub fn infinite(&self) -> bool {
+        self.infinite
+    }
+}
+
+impl Display for GoCommand {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        write!(formatter, "go")?;
+
+        if !self.search_moves.is_empty() {
+            write!(formatter, " searchmoves {}", self.search_moves.join(" "))?;
+        }
+
+        if self.ponder {
+            write!(formatter, " ponder")?;
+        }
+
+        let values = [
+            ("wtime", self.wtime),
+            ("btime", self.btime),
+            ("winc", self.winc),
+            ("binc", self.binc),
+            ("movestogo", self.moves_to_go),
+            ("depth", self.depth.map(u128::from)),
+            ("nodes", self.nodes.map(u128::from)),
+            ("mate", self.mate.map(u128::from)),
+            ("movetime", self.move_time),
+        ];
+
+        for (name, value) in values {
+            if let Some(value) = value {
+                write!(formatter, " {name} {value}")?;
+            }
+        }
+
+        if self.infinite {
+            write!(formatter, " infinite")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_value<T: FromStr>(name: &str, args: &[String], idx: &mut usize, diagnostics: &mut Vec<String>) -> Option<T> {
+    let Some(value) = args.get(*idx).filter(|value| !KEYWORDS.contains(&value.as_str())) else {
+        diagnostics.push(format!("Missing value for '{name}'"));
+        return None;
+    };
+
+    *idx += 1;
+
+    let result = value.parse::<T>().ok();
+    if result.is_none() {
+        diagnostics.push(format!("Invalid value '{value}' for '{name}'"));
+    }
+
+    result
+}
+
+//Some GUIs send negative clocks after running out of time, those count as no time left
+fn parse_time(name: &str, args: &[String], idx: &mut usize, diagnostics: &mut Vec<String>) -> Option<u128> {
+    parse_value::<i128>(name, args, idx, diagnostics).map(|time| time.max(0) as u128)
+}
@@ -7,7 +7,9 @@ pub struct TimeManager {
     soft_limit: Option<u128>,
     hard_limit: Option<u128>,
     previous_score: Option<f64>,
-    previous_best_move_changes: Option<f64>
+    previous_best_move_changes: Option<f64>,
+    //Time spent pondering before ponderhit, limits count from ponderhit
+    start_time: u128
 }
 
 #[allow(unused)]
@@ -65,12 +67,20 @@ impl TimeManager {
         self.hard_limit = Some(hard_time);
     } 
 
+    pub fn set_start_time(&mut self, start_time: u128) {
+        self.start_time = start_time
+    }
+
+    pub fn start_time(&self) -> u128 {
+        self.start_time
+    }
+
     pub fn hard_limit_reached(&mut self, search_stats: &SearchStats) -> bool {
         if self.soft_limit.is_none() || self.hard_limit.is_none() {
             return false;
         }
 
-        search_stats.time_passesd_ms() >= self.hard_limit.unwrap()
+        search_stats.time_passesd_ms() - self.start_time >= self.hard_limit.unwrap()
     }
 
     pub fn soft_limit_reached(&mut self, search_stats: &SearchStats, tree: &Tree, options: &EngineOptions, best_move_changes: usize) -> bool {
@@ -79,7 +89,7 @@ impl TimeManager {
         }
 
         let move_overhead = (options.move_overhead() + (options.threads() - 1) * 10) as u128;
-        let time_passed_ms = search_stats.time_passesd_ms();
+        let time_passed_ms = search_stats.time_passesd_ms() - self.start_time;
         
         let mut soft_limit_multiplier = 1.0;
 
@@ -0,0 +1,160 @@
+use std::fmt::{Display, Formatter, Result};
+
+use chess::Move;
+
+use crate::{GameState, PvLine, SearchEngine, SearchStats, WDLScore};
+
+//Tablebase results are reported as this score, evaluations stay below it
+const TABLEBASE_CP: i32 = 20000;
+
+//Score of a line from the side to move, mates are counted in moves and negative when getting mated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineScore {
+    Cp(i32),
+    Mate(i32),
+}
+
+impl Display for LineScore {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result {
+        match self {
+            LineScore::Cp(cp) => write!(formatter, "cp {cp}"),
+            LineScore::Mate(moves) => write!(formatter, "mate {moves}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchLine {
+    pv: PvLine,
+    wdl: WDLScore,
+    score: LineScore,
+}
+
+impl SearchLine {
+    fn new(pv: PvLine, search_engine: &SearchEngine) -> Self {
+        let score = pv.score();
+        let mut v = score.win_chance() - score.lose_chance();
+        let mut d = score.draw_chance();
+
+        search_engine.contempt().rescale(&mut v, &mut d, 1.0, true, search_engine.options());
+
+        let wdl = WDLScore::new((1.0 + v - d) / 2.0, d);
+
+        //States belong to the node after the first move, so they are seen from the opponent
+        let score = match pv.first_node().state() {
+            GameState::Loss(len) => LineScore::Mate(i32::from(len / 2 + 1)),
+            GameState::Win(len) => LineScore::Mate(-i32::from(len / 2 + 1)),
+            GameState::TablebaseLoss => LineScore::Cp(TABLEBASE_CP),
+            GameState::TablebaseWin => LineScore::Cp(-TABLEBASE_CP),
+            GameState::Draw => LineScore::Cp(0),
+            _ => LineScore::Cp(wdl.cp().clamp(1 - TABLEBASE_CP, TABLEBASE_CP - 1)),
+        };
+
+        Self { pv, wdl, score }
+    }
+
+    #[inline]
+    pub fn pv(&self) -> &PvLine {
+        &self.pv
+    }
+
+    //Contempt-adjusted WDL, raw statistics are in pv().score()
+    #[inline]
+    pub fn wdl(&self) -> WDLScore {
+        self.wdl
+    }
+
+    #[inline]
+    pub fn score(&self) -> LineScore {
+        self.score
+    }
+}
+
+//Snapshot of a search, either in progress or finished
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    best_move: Option<Move>,
+    ponder_move: Option<Move>,
+    lines: Vec<SearchLine>,
+    iterations: u64,
+    nodes: u64,
+    time_ms: u128,
+    avg_depth: u64,
+    max_depth: u64,
+}
+
+impl SearchResult {
+    pub fn new(search_stats: &SearchStats, search_engine: &SearchEngine) -> Self {
+        let tree = search_engine.tree();
+        let draw_score = search_engine.options().draw_score() as f64 / 100.0;
+        let best_node_idx = tree.select_best_child(tree.root_index(), draw_score);
+
+        let pv_count = tree.root_moves_count().min(search_engine.options().multi_pv() as usize);
+        let lines = (0..pv_count).map(|pv_idx| SearchLine::new(tree.get_best_pv(pv_idx, draw_score), search_engine)).collect();
+
+        let nodes = if search_engine.options().report_iters() {
+            search_stats.iterations()
+        } else {
+            search_stats.cumulative_depth()
+        };
+
+        Self {
+            best_move: best_node_idx.map(|node_idx| tree[node_idx].mv()),
+            ponder_move: best_node_idx.and_then(|node_idx| tree.get_pv(node_idx, draw_score, false).ponder_move()),
+            lines,
+            iterations: search_stats.iterations(),
+            nodes,
+            time_ms: search_stats.time_passesd_ms(),
+            avg_depth: search_stats.avg_depth(),
+            max_depth: search_stats.max_depth(),
+        }
+    }
+
+    //None when the root has no legal moves
+    #[inline]
+    pub fn best_move(&self) -> Option<Move> {
+        self.best_move
+    }
+
+    #[inline]
+    pub fn ponder_move(&self) -> Option<Move> {
+        self.ponder_move
+    }
+
+    //MultiPV lines, best first
+    #[inline]
+    pub fn lines(&self) -> &[SearchLine] {
+        &self.lines
+    }
+
+    #[inline]
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    //Iterations or cumulative depth, depending on Report_iters
+    #[inline]
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    #[inline]
+    pub fn nps(&self) -> u128 {
+        (self.nodes as u128 * 1000) / self.time_ms.max(1)
+    }
+
+    #[inline]
+    pub fn time_ms(&self) -> u128 {
+        self.time_ms
+    }
+
+    #[inline]
+    pub fn avg_depth(&self) -> u64 {
+        self.avg_depth
+    }
+
+    #[inline]
+    pub fn max_depth(&self) -> u64 {
+        self.max_depth
+    }
+}
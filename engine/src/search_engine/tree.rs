@@ -1,4 +1,4 @@
-use std::{ops::{Index, IndexMut}, sync::atomic::{AtomicU32, Ordering}};
+use std::{ops::{Index, IndexMut}, sync::{atomic::{AtomicU32, Ordering}, RwLock, RwLockReadGuard}};
 
 use chess::Move;
 
@@ -8,6 +8,8 @@ mod tree_draw;
 mod tree_utils;
 mod tree_lru;
 mod tree_reuse;
+mod tree_file;
+mod tree_transpositions;
 mod pv_line;
 mod half;
 
@@ -22,7 +24,11 @@ use crate::search_engine::hash_table::HashTable;
 pub struct Tree {
     halves: [TreeHalf; 2],
     current_half: AtomicU32,
+    //Changes whenever nodes are dropped or moved between halves, children blocks shared through the hash table are tied to it
+    generation: AtomicU32,
     hash_table: HashTable,
+    //Root children allowed by searchmoves, all of them when empty
+    root_moves: RwLock<Vec<Move>>,
 }
 
 impl Clone for Tree {
@@ -30,7 +36,9 @@ impl Clone for Tree {
         Self {
             halves: self.halves.clone(),
             current_half: AtomicU32::from(self.current_half.load(Ordering::Relaxed)),
-            hash_table: self.hash_table.clone()
+            generation: AtomicU32::from(self.generation.load(Ordering::Relaxed)),
+            hash_table: self.hash_table.clone(),
+            root_moves: RwLock::new(self.root_moves.read().unwrap().clone())
         }
     }
 }
@@ -61,20 +69,31 @@ impl Tree {
         Self {
             halves,
             current_half: AtomicU32::new(0),
+            generation: AtomicU32::new(1),
             hash_table: HashTable::new(hash_bytes),
+            root_moves: RwLock::new(Vec::new()),
         }
     }
 
     #[inline]
     pub fn clear(&self) {
+        self.clear_nodes();
+        self.hash_table.clear();
+    }
+
+    //Drops the nodes but keeps the hash table, its scores are still valid in any other position
+    #[inline]
+    pub fn clear_nodes(&self) {
         self.halves[0].clear();
         self.halves[1].clear();
-        self.hash_table.clear();
 
         self.current_half.store(0, Ordering::Relaxed);
+        self.generation.fetch_add(1, Ordering::Relaxed);
 
         self.halves[0].reserve_nodes(1);
         self[self.root_index()].clear(Move::NULL);
+
+        self.root_moves.write().unwrap().clear();
     }
 
     #[inline]
@@ -97,6 +116,27 @@ impl Tree {
         &self[self.root_index()]
     }
     
+    #[inline]
+    pub fn set_root_moves(&self, moves: &[Move]) {
+        *self.root_moves.write().unwrap() = moves.to_vec()
+    }
+
+    #[inline]
+    pub fn root_moves(&self) -> RwLockReadGuard<'_, Vec<Move>> {
+        self.root_moves.read().unwrap()
+    }
+
+    //Number of root children that can be searched and reported
+    #[inline]
+    pub fn root_moves_count(&self) -> usize {
+        let root_moves = self.root_moves();
+        if root_moves.is_empty() {
+            self.root_node().children_count()
+        } else {
+            root_moves.len()
+        }
+    }
+
     #[inline]
     pub fn max_size(&self) -> usize {
         self.halves[0].max_size() + self.halves[1].max_size()
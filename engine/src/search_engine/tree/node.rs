@@ -84,6 +84,39 @@ impl Node {
         self.clear_children();
     }
 
+    pub(super) const RECORD_SIZE: usize = 36;
+
+    //Everything but the children, little-endian, used by tree files
+    pub(super) fn record(&self) -> [u8; Self::RECORD_SIZE] {
+        let [win, draw] = self.cumulative_score.raw();
+
+        let mut record = [0u8; Self::RECORD_SIZE];
+        record[0..2].copy_from_slice(&self.mv.load(Ordering::Relaxed).to_le_bytes());
+        record[2..6].copy_from_slice(&self.visits().to_le_bytes());
+        record[6..14].copy_from_slice(&win.to_le_bytes());
+        record[14..22].copy_from_slice(&draw.to_le_bytes());
+        record[22..30].copy_from_slice(&self.squared_score.load(Ordering::Relaxed).to_le_bytes());
+        record[30..32].copy_from_slice(&self.policy.load(Ordering::Relaxed).to_le_bytes());
+        record[32..34].copy_from_slice(&self.state.raw());
+        record[34..36].copy_from_slice(&self.gini_impurity.load(Ordering::Relaxed).to_le_bytes());
+        record
+    }
+
+    pub(super) fn set_from_record(&self, record: &[u8; Self::RECORD_SIZE]) -> Result<(), String> {
+        let u16_at = |idx: usize| u16::from_le_bytes([record[idx], record[idx + 1]]);
+        let u64_at = |idx: usize| u64::from_le_bytes(record[idx..idx + 8].try_into().unwrap());
+
+        self.state.set_raw([record[32], record[33]])?;
+        self.mv.store(u16_at(0), Ordering::Relaxed);
+        self.visit_count.store(u32::from_le_bytes(record[2..6].try_into().unwrap()), Ordering::Relaxed);
+        self.cumulative_score.store_raw([u64_at(6), u64_at(14)]);
+        self.squared_score.store(u64_at(22), Ordering::Relaxed);
+        self.policy.store(u16_at(30), Ordering::Relaxed);
+        self.gini_impurity.store(u16_at(34), Ordering::Relaxed);
+        self.threads.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
     pub fn clear_children(&self) { 
         *self.children_index_mut() = NodeIndex::NULL;
         self.children_count.store(0, Ordering::Relaxed);
@@ -5,7 +5,10 @@ pub enum GameState {
     Ongoing,
     Draw,
     Win(u8),
-    Loss(u8)
+    Loss(u8),
+    //Proven by tablebases, the distance to mate is unknown
+    TablebaseWin,
+    TablebaseLoss
 }
 
 #[derive(Debug, Default)]
@@ -38,13 +41,30 @@ impl AtomicGameState {
                 self.state.store(2, Ordering::Relaxed);
                 self.payload.store(len, Ordering::Relaxed);
             },
+            GameState::TablebaseWin => self.state.store(3, Ordering::Relaxed),
             GameState::Loss(len) => {
                 self.state.store(4, Ordering::Relaxed);
                 self.payload.store(len, Ordering::Relaxed);
             },
+            GameState::TablebaseLoss => self.state.store(5, Ordering::Relaxed),
         }
     }
 
+    //State and payload bytes as stored in tree files
+    pub fn raw(&self) -> [u8; 2] {
+        [self.state.load(Ordering::Relaxed), self.payload.load(Ordering::Relaxed)]
+    }
+
+    pub fn set_raw(&self, raw: [u8; 2]) -> Result<(), String> {
+        if raw[0] > 5 {
+            return Err(format!("Invalid game state {}", raw[0]));
+        }
+
+        self.state.store(raw[0], Ordering::Relaxed);
+        self.payload.store(raw[1], Ordering::Relaxed);
+        Ok(())
+    }
+
     pub fn get(&self) -> GameState {
         let state = self.state.load(Ordering::Relaxed);
         let payload = self.payload.load(Ordering::Relaxed);
@@ -53,7 +73,9 @@ impl AtomicGameState {
             0 => GameState::Ongoing,
             1 => GameState::Draw,
             2 => GameState::Win(payload),
+            3 => GameState::TablebaseWin,
             4 => GameState::Loss(payload),
+            5 => GameState::TablebaseLoss,
             _ => unreachable!()
         }
     }
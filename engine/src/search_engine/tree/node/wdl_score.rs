@@ -44,6 +44,18 @@ impl AtomicWDLScore {
         WDLScore(win_chance, draw_chance)
     }
 
+    //Fixed-point win and draw sums
+    #[inline]
+    pub fn raw(&self) -> [u64; 2] {
+        [self.0.load(Ordering::Relaxed), self.1.load(Ordering::Relaxed)]
+    }
+
+    #[inline]
+    pub fn store_raw(&self, raw: [u64; 2]) {
+        self.0.store(raw[0], Ordering::Relaxed);
+        self.1.store(raw[1], Ordering::Relaxed);
+    }
+
     #[inline]
     pub fn clear(&self) {
         self.0.store(0, Ordering::Relaxed);
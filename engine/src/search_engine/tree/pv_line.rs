@@ -30,6 +30,12 @@ impl PvLine {
         self.0[0].clone()
     }
 
+    //Expected reply to the first move, the move to ponder on
+    #[inline]
+    pub fn ponder_move(&self) -> Option<Move> {
+        self.0.get(1).filter(|node| node.visits() > 0).map(Node::mv)
+    }
+
     #[inline]
     pub fn score(&self) -> WDLScore {
         self.0[0].score()
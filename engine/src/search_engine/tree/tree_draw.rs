@@ -223,6 +223,8 @@ impl Tree {
             match node.state() {
                 GameState::Loss(x) => GameState::Win(x),
                 GameState::Win(x) => GameState::Loss(x),
+                GameState::TablebaseLoss => GameState::TablebaseWin,
+                GameState::TablebaseWin => GameState::TablebaseLoss,
                 _ => node.state()
             }
         } else {
@@ -245,6 +247,8 @@ impl Tree {
             GameState::Draw => String::from("DRAW"),
             GameState::Win(len) => format!("WIN IN {len}"),
             GameState::Loss(len) => format!("LOSS IN {len}"),
+            GameState::TablebaseWin => String::from("TB WIN"),
+            GameState::TablebaseLoss => String::from("TB LOSS"),
             _ => String::new(),
         };
 
@@ -1,9 +1,12 @@
-use chess::ChessBoard;
+use chess::{ChessBoard, Move};
 
-use crate::{search_engine::engine_options::EngineOptions, NodeIndex, PolicyNetwork, Tree};
+use crate::{search_engine::engine_options::EngineOptions, networks::{policy_network, PolicyCache}, NodeIndex, Tree};
 
 impl Tree {
-    pub fn expand_node(&self, node_idx: NodeIndex, depth: f64, board: &ChessBoard, engine_options: &EngineOptions) -> Option<()> {
+    //Mate searches favour forcing moves, checks, captures and promotions get a policy bonus
+    const FORCING_MOVE_BONUS: f64 = 2.0;
+
+    pub fn expand_node(&self, node_idx: NodeIndex, depth: f64, board: &ChessBoard, engine_options: &EngineOptions, favour_forcing: bool) -> Option<()> {
         let mut children_idx = self[node_idx].children_index_mut();
 
         if self[node_idx].children_count() > 0 {
@@ -16,8 +19,8 @@ impl Tree {
             "Node {node_idx} already have children."
         );
 
-        let policy_inputs = PolicyNetwork.get_inputs(board);
-        let mut policy_cache: [Option<Vec<f32>>; 192] = [const { None }; 192];
+        let policy_inputs = policy_network().get_inputs(board);
+        let mut policy_cache = PolicyCache::default();
 
         let pst = if node_idx == self.root_index() {
             3.25
@@ -30,9 +33,15 @@ impl Tree {
         let mut max = f64::NEG_INFINITY;
         let mut total = 0f64;
 
+        let castle_mask = board.castle_rights().get_castle_mask();
+
         board.map_legal_moves(|mv| {
             moves.push(mv);
-            let p = PolicyNetwork.forward(board, &policy_inputs, mv, &mut policy_cache) as f64;
+            let mut p = policy_network().forward(board, &policy_inputs, mv, &mut policy_cache) as f64;
+            if favour_forcing && is_forcing(board, mv, &castle_mask) {
+                p += Self::FORCING_MOVE_BONUS;
+            }
+
             policy.push(p);
             max = max.max(p);
         });
@@ -95,8 +104,8 @@ impl Tree {
             return;
         }
 
-        let policy_inputs = PolicyNetwork.get_inputs(board);
-        let mut policy_cache: [Option<Vec<f32>>; 192] = [const { None }; 192];
+        let policy_inputs = policy_network().get_inputs(board);
+        let mut policy_cache = PolicyCache::default();
 
         let pst = if node_idx == self.root_index() {
             3.25
@@ -110,7 +119,7 @@ impl Tree {
 
         self[node_idx].map_children(|child_idx| {
             let mv = self[child_idx].mv();
-            let p = PolicyNetwork.forward(board, &policy_inputs, mv, &mut policy_cache) as f64;
+            let p = policy_network().forward(board, &policy_inputs, mv, &mut policy_cache) as f64;
             policy.push(p);
             max = max.max(p);
         });
@@ -137,6 +146,16 @@ impl Tree {
     }
 }
 
+fn is_forcing(board: &ChessBoard, mv: Move, castle_mask: &[u8; 64]) -> bool {
+    if mv.is_capture() || mv.is_promotion() {
+        return true;
+    }
+
+    let mut board_copy = *board;
+    board_copy.make_move(mv, castle_mask);
+    board_copy.is_in_check()
+}
+
 fn calculate_pst(options: &EngineOptions, parent_score: f64, depth: f64) -> f64 {
     let scalar = parent_score - parent_score.min(options.winning_pst_threshold());
     let t = scalar / (1.0 - options.winning_pst_threshold());
@@ -0,0 +1,145 @@
+use std::{collections::{HashSet, VecDeque}, fs::File, io::{BufReader, BufWriter, Read, Write}};
+
+use chess::{ChessBoard, ChessPosition, FEN};
+
+use crate::{Node, NodeIndex, Tree};
+
+//-----------------------------------------------
+// Tree file layout, all little-endian:
+//   magic, version, FEN length (u32), FEN, node count (u64)
+//   per node: children count (u8), node record
+// Nodes are stored breadth-first from the root, so the children of
+// every node follow each other just like they do in the tree.
+//-----------------------------------------------
+
+const TREE_FILE_MAGIC: [u8; 4] = *b"JKTR";
+const TREE_FILE_VERSION: u32 = 1;
+const MAX_FEN_LENGTH: usize = 128;
+
+impl Tree {
+    pub fn save(&self, path: &str, position: &ChessPosition) -> Result<(), String> {
+        let file = File::create(path).map_err(|err| format!("Failed to create '{path}': {err}"))?;
+        let mut writer = BufWriter::new(file);
+
+        //Children shared by transpositions are saved under their first parent, the others are saved as
+        //leaves and join the block again, or expand, once they are searched
+        let mut nodes = Vec::new();
+        let mut saved_children = HashSet::new();
+        let mut queue = VecDeque::from([self.root_index()]);
+        while let Some(node_idx) = queue.pop_front() {
+            let node = &self[node_idx];
+            let expanded = node.children_count() > 0 && saved_children.insert(u32::from(*node.children_index()));
+
+            nodes.push((node_idx, expanded));
+            if expanded {
+                node.map_children(|child_idx| queue.push_back(child_idx));
+            }
+        }
+
+        let fen = FEN::from(position.board()).to_string();
+
+        let write_error = |err: std::io::Error| format!("Failed to write '{path}': {err}");
+
+        writer.write_all(&TREE_FILE_MAGIC).map_err(write_error)?;
+        writer.write_all(&TREE_FILE_VERSION.to_le_bytes()).map_err(write_error)?;
+        writer.write_all(&(fen.len() as u32).to_le_bytes()).map_err(write_error)?;
+        writer.write_all(fen.as_bytes()).map_err(write_error)?;
+        writer.write_all(&(nodes.len() as u64).to_le_bytes()).map_err(write_error)?;
+
+        for (node_idx, expanded) in nodes {
+            let node = &self[node_idx];
+            let children_count = if expanded { node.children_count() as u8 } else { 0 };
+            writer.write_all(&[children_count]).map_err(write_error)?;
+            writer.write_all(&node.record()).map_err(write_error)?;
+        }
+
+        writer.flush().map_err(write_error)
+    }
+
+    //Replaces the tree with the saved one and returns its root position. When the tree is too small
+    //the deepest nodes are dropped, their parents expand again during search.
+    pub fn load(&self, path: &str) -> Result<ChessPosition, String> {
+        let file = File::open(path).map_err(|err| format!("Failed to open '{path}': {err}"))?;
+        let mut reader = BufReader::new(file);
+
+        let corrupted = |_| format!("'{path}' is not a valid tree file");
+
+        let mut header = [0u8; 12];
+        reader.read_exact(&mut header).map_err(corrupted)?;
+
+        if header[0..4] != TREE_FILE_MAGIC {
+            return Err(format!("'{path}' is not a valid tree file"));
+        }
+
+        let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        if version != TREE_FILE_VERSION {
+            return Err(format!("Unsupported tree file version {version}, expected {TREE_FILE_VERSION}"));
+        }
+
+        let fen_length = u32::from_le_bytes(header[8..12].try_into().unwrap()) as usize;
+        if fen_length > MAX_FEN_LENGTH {
+            return Err(format!("'{path}' is not a valid tree file"));
+        }
+
+        let mut fen = vec![0u8; fen_length];
+        reader.read_exact(&mut fen).map_err(corrupted)?;
+
+        let fen = String::from_utf8(fen).map_err(|_| format!("'{path}' is not a valid tree file"))?;
+        if !FEN::validate_fen(&fen) {
+            return Err(format!("Invalid position '{fen}' in '{path}'"));
+        }
+
+        let mut node_count = [0u8; 8];
+        reader.read_exact(&mut node_count).map_err(corrupted)?;
+
+        self.clear();
+
+        if let Err(err) = self.load_nodes(&mut reader, u64::from_le_bytes(node_count), path) {
+            self.clear();
+            return Err(err);
+        }
+
+        Ok(ChessPosition::from(ChessBoard::from(&FEN::from(fen))))
+    }
+
+    fn load_nodes(&self, reader: &mut impl Read, node_count: u64, path: &str) -> Result<(), String> {
+        let corrupted = |_| format!("'{path}' is not a valid tree file");
+
+        //Tree slots waiting for the next records in file order, None for nodes that did not fit
+        let mut targets = VecDeque::from([Some(self.root_index())]);
+        let mut record = [0u8; Node::RECORD_SIZE];
+
+        for _ in 0..node_count {
+            let mut children_count = [0u8];
+            reader.read_exact(&mut children_count).map_err(corrupted)?;
+            reader.read_exact(&mut record).map_err(corrupted)?;
+
+            let children_count = usize::from(children_count[0]);
+            let target = targets.pop_front().ok_or_else(|| format!("'{path}' is not a valid tree file"))?;
+
+            let Some(node_idx) = target else {
+                targets.extend(std::iter::repeat_n(None, children_count));
+                continue;
+            };
+
+            let node = &self[node_idx];
+            node.clear_children();
+            node.set_from_record(&record)?;
+
+            if children_count == 0 {
+                continue;
+            }
+
+            match self.current_half().reserve_nodes(children_count) {
+                Some(children_idx) => {
+                    *node.children_index_mut() = children_idx;
+                    node.set_children_count(children_count);
+                    targets.extend((0..children_count).map(|idx| Some(children_idx + idx)));
+                },
+                None => targets.extend(std::iter::repeat_n(None::<NodeIndex>, children_count)),
+            }
+        }
+
+        Ok(())
+    }
+}
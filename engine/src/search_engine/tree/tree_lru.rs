@@ -6,6 +6,7 @@ impl Tree {
     pub fn swap_half(&self) {
         let old_root = self.root_index();
         let old_half = self.current_half.fetch_xor(1, std::sync::atomic::Ordering::Relaxed) as usize;
+        self.generation.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
         self.halves[old_half].clear_references();
         self.halves[old_half ^ 1].clear();
@@ -1,30 +1,29 @@
-use chess::ChessPosition;
+use std::collections::HashSet;
 
-use crate::{search_engine::engine_options::EngineOptions, NodeIndex, Tree};
+use chess::{CastleRights, ChessBoard, ChessPosition, Piece, Side};
+
+use crate::{search_engine::engine_options::EngineOptions, GameState, NodeIndex, Tree};
 
 impl Tree {
-    pub fn try_reuse(&self, position: &ChessPosition, target: &ChessPosition, options: &EngineOptions) -> Option<()> {
+    //Moves the subtree of the target position to the root. The target is the given number of plies deep and is
+    //matched by hash, so a transposition of it works as well. When it's not in the tree, only the hash table is kept.
+    pub fn try_reuse(&self, position: &ChessPosition, target: &ChessPosition, plies: u32, options: &EngineOptions) -> Option<()> {
         if position.board().hash() == target.board().hash() {
             return Some(())
         }
-       
-        let result = self.find_node(self.root_index(), position, target, 2);
-        if result.is_none() {
-            self.clear();
-            return None;
-        }
 
-        let result = result.unwrap();
+        let mut result = None;
+        self.find_node(self.root_index(), position.board(), target.board(), plies, &mut result);
+
+        let Some(result) = result else {
+            self.clear_nodes();
+            return None;
+        };
 
         let new_root = &self[result];
         let children_idx = *new_root.children_index();
         let count = new_root.children_count();
 
-        if children_idx.is_null() {
-            self.clear();
-            return None;
-        }
-
         let old_root_children_idx = *self.root_node().children_index();
 
         self[self.root_index()].set_to(new_root);
@@ -32,18 +31,32 @@ impl Tree {
 
         self.copy_across(children_idx, count, old_root_children_idx);
 
+        if self.root_node().state() == GameState::Draw {
+            self.set_state(self.root_index(), GameState::Ongoing);
+        }
+
+        self.reset_draws(self.root_index(), &mut HashSet::new());
         self.relabel_root(target.board(), options);
 
         Some(())
     }
 
-    fn find_node(&self, node_idx: NodeIndex, position: &ChessPosition, target: &ChessPosition, depth: u8) -> Option<NodeIndex> {
-        if position.board().hash() == target.board().hash() {
-            return Some(node_idx)
+    //Looks for an expanded node with the target hash exactly plies deep, following the most visited lines first
+    fn find_node(&self, node_idx: NodeIndex, board: &ChessBoard, target: &ChessBoard, plies: u32, result: &mut Option<NodeIndex>) {
+        if self[node_idx].children_count() == 0 || result.is_some() {
+            return
         }
 
-        if depth == 0 {
-            return None
+        if plies == 0 {
+            if board.hash() == target.hash() {
+                *result = Some(node_idx);
+            }
+
+            return
+        }
+
+        if !can_reach(board, target) {
+            return
         }
 
         let mut children = Vec::new();
@@ -53,18 +66,53 @@ impl Tree {
 
         children.sort_by_key(|&a| u32::MAX - self[a].visits());
 
-        let mask = position.board().castle_rights().get_castle_mask();
+        let mask = board.castle_rights().get_castle_mask();
         for child_idx in children {
-            let mut new_pos = position.clone();
-            new_pos.make_move(self[child_idx].mv(), &mask);
-            let result = self.find_node(child_idx, &new_pos, target, depth - 1);
-            if result.is_none() {
-                continue
-            }
+            let mut new_board = *board;
+            new_board.make_move(self[child_idx].mv(), &mask);
+            self.find_node(child_idx, &new_board, target, plies - 1, result);
+        }
+    }
 
-            return result
+    //Repetitions are counted from the game history, which is different at the new root. Proven draws are
+    //searched again and draws found at leaves are evaluated again on their next visit.
+    fn reset_draws(&self, node_idx: NodeIndex, visited: &mut HashSet<u32>) {
+        let node = &self[node_idx];
+        if node.children_count() == 0 || !visited.insert(u32::from(*node.children_index())) {
+            return
         }
 
-        None
+        node.map_children(|child_idx| {
+            let child = &self[child_idx];
+
+            if child.state() == GameState::Draw {
+                if child.children_count() > 0 {
+                    child.set_state(GameState::Ongoing);
+                } else {
+                    let policy = child.policy();
+                    child.clear(child.mv());
+                    child.set_policy(policy);
+                }
+            }
+
+            self.reset_draws(child_idx, visited);
+        });
     }
-}
\ No newline at end of file
+}
+
+//Pieces, pawns and castle rights can only be lost, so subtrees that already lost any of them can be skipped
+fn can_reach(board: &ChessBoard, target: &ChessBoard) -> bool {
+    if board.occupancy().pop_count() < target.occupancy().pop_count() {
+        return false
+    }
+
+    for side in [Side::WHITE, Side::BLACK] {
+        if board.piece_mask_for_side(Piece::PAWN, side).pop_count() < target.piece_mask_for_side(Piece::PAWN, side).pop_count() {
+            return false
+        }
+    }
+
+    [CastleRights::WHITE_KING, CastleRights::WHITE_QUEEN, CastleRights::BLACK_KING, CastleRights::BLACK_QUEEN]
+        .into_iter()
+        .all(|right| !target.castle_rights().has_right(right) || board.castle_rights().has_right(right))
+}
@@ -0,0 +1,84 @@
+use std::sync::atomic::Ordering;
+
+use chess::{ChessBoard, ZobristKey};
+
+use crate::{NodeIndex, Tree};
+
+//-----------------------------------------------
+// With transpositions enabled, nodes of the same position share one children
+// block, so the visits, scores and proofs below it are gathered once for every
+// path. Only positions right after a zeroing move are shared. Their move history
+// starts over, which keeps repetition and 50-move draws below them exact no matter
+// how they were reached. The block is found through the hash table and only trusted
+// in the tree generation that stored it, half swaps and clears start a new one.
+//-----------------------------------------------
+
+impl Tree {
+    #[inline]
+    pub fn generation(&self) -> u32 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    //Points a node without children in the current half at the block of a transposition. After a half swap
+    //this joins the copy another parent already made, instead of copying the block a second time.
+    pub fn link_children(&self, node_idx: NodeIndex, key: ZobristKey, board: &ChessBoard) -> bool {
+        let node = &self[node_idx];
+        if node.children_count() > 0 && node.children_index().half() == self.current_half_index() {
+            return false;
+        }
+
+        let Some(shared_idx) = self.hash_table().children(key, self.generation()) else {
+            return false;
+        };
+
+        let mut children_idx = node.children_index_mut();
+        if node.children_count() > 0 && children_idx.half() == self.current_half_index() {
+            return false;
+        }
+
+        let Some(count) = self.shared_block_size(shared_idx, board) else {
+            return false;
+        };
+
+        *children_idx = shared_idx;
+        node.set_children_count(count);
+
+        let squares = (0..count).map(|idx| self[shared_idx + idx].policy().powi(2)).sum::<f64>();
+        node.set_gini_impurity((1.0 - squares).clamp(0.0, 1.0));
+
+        true
+    }
+
+    //Offers the children of the node to its transpositions, unless the position already has a block
+    pub fn share_children(&self, node_idx: NodeIndex, key: ZobristKey) {
+        let node = &self[node_idx];
+        let children_idx = *node.children_index();
+
+        if node.children_count() == 0 || children_idx.half() != self.current_half_index() {
+            return;
+        }
+
+        if self.hash_table().children(key, self.generation()).is_none() {
+            self.hash_table().set_children(key, children_idx, self.generation());
+        }
+    }
+
+    //Hash entries can be replaced by another position at any time, so the block has to hold
+    //exactly the legal moves of the board, in the order they were expanded
+    fn shared_block_size(&self, children_idx: NodeIndex, board: &ChessBoard) -> Option<usize> {
+        if children_idx.half() != self.current_half_index() {
+            return None;
+        }
+
+        let used = self.current_half().current_size().min(self.current_half().max_size());
+
+        let mut count = 0;
+        let mut matches = true;
+        board.map_legal_moves(|mv| {
+            matches &= (children_idx.idx() as usize + count) < used && self[children_idx + count].mv() == mv;
+            count += 1;
+        });
+
+        (matches && count > 0).then_some(count)
+    }
+}
@@ -1,4 +1,6 @@
-use crate::search_engine::{tree::{node::Node, pv_line::PvLine, NodeIndex, Tree}};
+use chess::Move;
+
+use crate::search_engine::{tree::{node::Node, pv_line::PvLine, GameState, NodeIndex, Tree}};
 
 impl Tree {
     pub fn bytes_to_size(bytes: usize) -> usize {
@@ -9,15 +11,22 @@ impl Tree {
         size * std::mem::size_of::<Node>()
     }
 
+    //Only children playing one of the allowed moves are considered. When the list is empty or none of
+    //them is a child, every child is.
     pub fn select_child_by_key<F: FnMut(&Node) -> f64>(
         &self,
         parent_idx: NodeIndex,
+        allowed: &[Move],
         mut key: F,
     ) -> Option<NodeIndex> {
         let mut best_idx = None;
         let mut best_score = f64::NEG_INFINITY;
 
         self[parent_idx].map_children(|child_idx| {
+            if !allowed.is_empty() && !allowed.contains(&self[child_idx].mv()) {
+                return;
+            }
+
             let new_score = key(&self[child_idx]);
             if new_score > best_score {
                 best_idx = Some(child_idx);
@@ -25,11 +34,18 @@ impl Tree {
             }
         });
 
+        if best_idx.is_none() && !allowed.is_empty() {
+            return self.select_child_by_key(parent_idx, &[], key);
+        }
+
         best_idx
     }
 
     pub fn select_best_child(&self, parent_idx: NodeIndex, draw_score: f64) -> Option<NodeIndex> {
-        self.select_child_by_key(parent_idx, |node| node.score().single_with_score(draw_score) as f64)
+        let root_moves = (parent_idx == self.root_index()).then(|| self.root_moves());
+        let allowed = root_moves.as_deref().map_or(&[][..], Vec::as_slice);
+
+        self.select_child_by_key(parent_idx, allowed, |node| best_child_key(node, draw_score))
     }
 
     pub fn get_pv(&self, node_idx: NodeIndex, draw_score: f64, flip: bool) -> PvLine {
@@ -60,11 +76,12 @@ impl Tree {
     pub fn get_best_pv(&self, index: usize, draw_score: f64) -> PvLine {
         let mut chilren_nodes = Vec::new();
         let node = self.root_node();
+        let root_moves = self.root_moves();
 
         node.map_children(|child_idx| {
             let node = &self[child_idx];
 
-            if node.visits() == 0 {
+            if node.visits() == 0 || (!root_moves.is_empty() && !root_moves.contains(&node.mv())) {
                 return;
             }
 
@@ -120,4 +137,49 @@ impl Tree {
 
         return None;
     }
-}
\ No newline at end of file
+
+    //Moves the kept root children to the front and renormalises their policy. Nothing is removed
+    //when no child would be kept.
+    pub fn retain_root_children<F: FnMut(Move) -> bool>(&self, mut keep: F) {
+        let root = self.root_node();
+        let children_idx = *root.children_index();
+
+        let kept = (0..root.children_count()).filter(|&idx| keep(self[children_idx + idx].mv())).collect::<Vec<_>>();
+        if kept.is_empty() || kept.len() == root.children_count() {
+            return;
+        }
+
+        let mut total_policy = 0.0;
+        for (target_idx, &source_idx) in kept.iter().enumerate() {
+            let target = &self[children_idx + target_idx];
+            let source = &self[children_idx + source_idx];
+
+            if target_idx != source_idx {
+                target.set_to(source);
+                *target.children_index_mut() = *source.children_index();
+                target.set_children_count(source.children_count());
+            }
+
+            total_policy += target.policy();
+        }
+
+        root.set_children_count(kept.len());
+
+        for idx in 0..kept.len() {
+            let child = &self[children_idx + idx];
+            child.set_policy(if total_policy > 0.0 { child.policy() / total_policy } else { 1.0 / kept.len() as f64 });
+        }
+    }
+}
+
+//Proven mates outrank any score, the shortest mate first and the longest defence when mated.
+//Tablebase wins come right after mates and tablebase losses right before being mated.
+fn best_child_key(node: &Node, draw_score: f64) -> f64 {
+    match node.state() {
+        GameState::Loss(len) => 3.0 - f64::from(len) / 256.0,
+        GameState::Win(len) => -3.0 + f64::from(len) / 256.0,
+        GameState::TablebaseLoss => 1.5 + node.score().single_with_score(draw_score) / 4.0,
+        GameState::TablebaseWin => -1.75 + node.score().single_with_score(draw_score) / 4.0,
+        _ => node.score().single_with_score(draw_score)
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+#[derive(Debug)]
+struct Worker {
+    sender: Option<Sender<Job>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let handle = thread::spawn(move || {
+            //Parked on the channel until the next job, closing the channel ends the thread
+            for job in receiver {
+                job();
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+//Long-lived helper threads of the search, they are kept between searches and half swaps
+#[derive(Debug, Default)]
+pub struct WorkerPool {
+    workers: Vec<Worker>,
+}
+
+impl WorkerPool {
+    pub fn new(size: usize) -> Self {
+        let mut pool = Self::default();
+        pool.resize(size);
+        pool
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn resize(&mut self, size: usize) {
+        self.workers.truncate(size);
+        while self.workers.len() < size {
+            self.workers.push(Worker::spawn());
+        }
+    }
+
+    //Runs the job on every worker and main on the calling thread. Returns once all of them are done,
+    //which is what allows the job to borrow from the caller. Panics are resumed after that point.
+    pub fn run<R>(&self, job: &(dyn Fn() + Sync), main: impl FnOnce() -> R) -> R {
+        //SAFETY: the reference never outlives this call, every copy is dropped before the loop below ends
+        let job: &'static (dyn Fn() + Sync) = unsafe { std::mem::transmute(job) };
+
+        let (done_sender, done_receiver) = mpsc::channel();
+        for worker in &self.workers {
+            let done_sender = done_sender.clone();
+            let task: Job = Box::new(move || {
+                let _ = done_sender.send(panic::catch_unwind(AssertUnwindSafe(job)));
+            });
+
+            //A failed send drops the task, and its sender with it
+            let _ = worker.sender.as_ref().map(|sender| sender.send(task));
+        }
+
+        drop(done_sender);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(main));
+
+        let mut worker_panic = None;
+        for worker_result in done_receiver {
+            if let Err(payload) = worker_result {
+                worker_panic = Some(payload);
+            }
+        }
+
+        match (result, worker_panic) {
+            (Err(payload), _) | (Ok(_), Some(payload)) => panic::resume_unwind(payload),
+            (Ok(result), None) => result,
+        }
+    }
+}
@@ -1,18 +1,74 @@
-use crate::{search_engine::SearchStats, SearchEngine, SearchLimits};
+use std::sync::mpsc::Sender;
 
-pub trait SearchReport {
-    fn refresh_rate_per_second() -> f64;
+use chess::Move;
+
+use crate::{search_engine::SearchStats, GameState, SearchEngine, SearchLimits, SearchResult};
+
+//Receives the events of a single search. The reporter lives on the thread of the main search loop,
+//so it can keep its own state between calls.
+pub trait SearchReport: Send {
+    fn refresh_rate_per_second(&self) -> f64;
+    //Called before search_started when the root kept visits from a previous search
+    #[allow(unused)]
+    fn tree_reused(&mut self, search_limits: &SearchLimits, carried_visits: u32, search_engine: &SearchEngine) { }
     #[allow(unused)]
-    fn search_started(search_limits: &SearchLimits, search_engine: &SearchEngine) { }
+    fn search_started(&mut self, search_limits: &SearchLimits, search_engine: &SearchEngine) { }
     #[allow(unused)]
-    fn search_report(search_limits: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) { }
+    fn search_report(&mut self, search_limits: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) { }
     #[allow(unused)]
-    fn search_ended(search_limits: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) { }
+    fn best_move_changed(&mut self, search_limits: &SearchLimits, search_stats: &SearchStats, best_move: Move, search_engine: &SearchEngine) { }
+    //Called once the root becomes a proven win, loss or draw
+    #[allow(unused)]
+    fn proof_found(&mut self, search_limits: &SearchLimits, search_stats: &SearchStats, state: GameState, search_engine: &SearchEngine) { }
+    #[allow(unused)]
+    fn search_ended(&mut self, search_limits: &SearchLimits, search_result: &SearchResult, search_engine: &SearchEngine) { }
 }
 
 pub struct NoReport;
 impl SearchReport for NoReport {
-    fn refresh_rate_per_second() -> f64 {
+    fn refresh_rate_per_second(&self) -> f64 {
+        1.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SearchEvent {
+    TreeReused { carried_visits: u32 },
+    Started,
+    Info(SearchResult),
+    BestMoveChanged { time_ms: u128, best_move: Move },
+    ProofFound { time_ms: u128, state: GameState },
+    Ended(SearchResult),
+}
+
+//Forwards every event into a channel, for embedders that consume the search from another thread.
+//Events are dropped silently once the receiver is gone.
+impl SearchReport for Sender<SearchEvent> {
+    fn refresh_rate_per_second(&self) -> f64 {
         1.0
     }
-}
\ No newline at end of file
+
+    fn tree_reused(&mut self, _: &SearchLimits, carried_visits: u32, _: &SearchEngine) {
+        let _ = self.send(SearchEvent::TreeReused { carried_visits });
+    }
+
+    fn search_started(&mut self, _: &SearchLimits, _: &SearchEngine) {
+        let _ = self.send(SearchEvent::Started);
+    }
+
+    fn search_report(&mut self, _: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) {
+        let _ = self.send(SearchEvent::Info(SearchResult::new(search_stats, search_engine)));
+    }
+
+    fn best_move_changed(&mut self, _: &SearchLimits, search_stats: &SearchStats, best_move: Move, _: &SearchEngine) {
+        let _ = self.send(SearchEvent::BestMoveChanged { time_ms: search_stats.time_passesd_ms(), best_move });
+    }
+
+    fn proof_found(&mut self, _: &SearchLimits, search_stats: &SearchStats, state: GameState, _: &SearchEngine) {
+        let _ = self.send(SearchEvent::ProofFound { time_ms: search_stats.time_passesd_ms(), state });
+    }
+
+    fn search_ended(&mut self, _: &SearchLimits, search_result: &SearchResult, _: &SearchEngine) {
+        let _ = self.send(SearchEvent::Ended(search_result.clone()));
+    }
+}
@@ -0,0 +1,8 @@
+mod encoding;
+mod material;
+mod probe;
+mod syzygy_loader;
+mod table;
+
+pub use probe::{probe_dtz, probe_root, probe_wdl, TablebaseWDL};
+pub use syzygy_loader::{load_syzygy, syzygy_max_pieces};
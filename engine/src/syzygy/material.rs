@@ -0,0 +1,69 @@
+use chess::{ChessBoard, Piece, Side};
+
+const PIECE_CHARS: [char; 5] = ['P', 'N', 'B', 'R', 'Q'];
+
+//Material signature of a table, parsed from its file name (e.g. KRPvKR).
+#[derive(Debug, Clone)]
+pub(super) struct Material {
+    //Key with the table's white pieces as white, key2 with colors swapped
+    pub key: u64,
+    pub key2: u64,
+    pub piece_count: usize,
+    pub has_pawns: bool,
+    pub has_unique_pieces: bool,
+    //Pawns of the leading color first, that is the side with fewer pawns when both have some
+    pub pawn_count: [u8; 2],
+}
+
+impl Material {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (white, black) = name.split_once('v')?;
+        let counts = [parse_side(white)?, parse_side(black)?];
+
+        let piece_count = 2 + counts.iter().flatten().map(|&count| usize::from(count)).sum::<usize>();
+        let [white_pawns, black_pawns] = [counts[0][0], counts[1][0]];
+
+        let lead_white = black_pawns == 0 || (white_pawns > 0 && black_pawns >= white_pawns);
+        let pawn_count = if lead_white { [white_pawns, black_pawns] } else { [black_pawns, white_pawns] };
+
+        Some(Self {
+            key: key_from_counts(&counts[0], &counts[1]),
+            key2: key_from_counts(&counts[1], &counts[0]),
+            piece_count,
+            has_pawns: white_pawns + black_pawns > 0,
+            has_unique_pieces: counts.iter().flatten().any(|&count| count == 1),
+            pawn_count,
+        })
+    }
+}
+
+pub(super) fn material_key(board: &ChessBoard) -> u64 {
+    let counts = [Side::WHITE, Side::BLACK].map(|side| {
+        let mut counts = [0u8; 5];
+        for (piece, count) in counts.iter_mut().enumerate() {
+            *count = board.piece_mask_for_side(Piece::from(piece), side).pop_count() as u8;
+        }
+        counts
+    });
+
+    key_from_counts(&counts[0], &counts[1])
+}
+
+fn key_from_counts(white: &[u8; 5], black: &[u8; 5]) -> u64 {
+    white.iter().chain(black.iter()).enumerate().fold(0, |key, (idx, &count)| key | u64::from(count) << (idx * 4))
+}
+
+//Counts of P, N, B, R, Q for one side, the name has to start with the king
+fn parse_side(pieces: &str) -> Option<[u8; 5]> {
+    let mut chars = pieces.chars();
+    if chars.next()? != 'K' {
+        return None;
+    }
+
+    let mut counts = [0u8; 5];
+    for piece in chars {
+        counts[PIECE_CHARS.iter().position(|&c| c == piece)?] += 1;
+    }
+
+    Some(counts)
+}
@@ -0,0 +1,243 @@
+use chess::{ChessBoard, ChessPosition, Move, Piece};
+
+use crate::syzygy::{
+    material::material_key,
+    syzygy_loader::{current_tablebases, Tablebases},
+    table::{TableProbe, TableType},
+};
+
+//Tablebase result from the side to move perspective. Cursed wins and blessed losses
+//are wins and losses that the 50-move rule turns into draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TablebaseWDL {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl TablebaseWDL {
+    #[inline]
+    fn from_value(value: i32) -> Self {
+        match value {
+            ..=-2 => Self::Loss,
+            -1 => Self::BlessedLoss,
+            0 => Self::Draw,
+            1 => Self::CursedWin,
+            _ => Self::Win,
+        }
+    }
+
+    #[inline]
+    fn value(self) -> i32 {
+        self as i32 - 2
+    }
+
+    #[inline]
+    pub fn reversed(self) -> Self {
+        Self::from_value(-self.value())
+    }
+}
+
+//WDL of the position, probes captures as tables store "don't care" values when a capture is best.
+pub fn probe_wdl(board: &ChessBoard) -> Option<TablebaseWDL> {
+    let tablebases = probeable_tablebases(board)?;
+    search(tablebases, board, false).map(|(wdl, _)| wdl)
+}
+
+//Plies to the next zeroing move, positive when winning and negative when losing. Values above 100
+//are cursed wins and blessed losses.
+pub fn probe_dtz(board: &ChessBoard) -> Option<i32> {
+    let tablebases = probeable_tablebases(board)?;
+    dtz(tablebases, board)
+}
+
+//Root moves that keep the tablebase result. When winning every move that still converts within the
+//50-move budget is kept, when losing every move unless a 50-move draw is close and when drawing only drawing moves.
+pub fn probe_root(position: &ChessPosition) -> Option<Vec<Move>> {
+    let board = position.board();
+    let tablebases = probeable_tablebases(board)?;
+
+    let half_moves = i32::from(board.half_moves());
+    let repeated = position.history().get_repetitions(board.hash()) > 1;
+    let mask = board.castle_rights().get_castle_mask();
+
+    let mut moves = Vec::new();
+    board.map_legal_moves(|mv| moves.push(mv));
+
+    let mut ranked = Vec::with_capacity(moves.len());
+    for mv in moves {
+        let mut child = *position;
+        child.make_move(mv, &mask);
+
+        let dtz = if child.board().half_moves() == 0 {
+            dtz_before_zeroing(search(tablebases, child.board(), false)?.0.reversed())
+        } else if child.board().half_moves() >= 100 || child.history().get_repetitions(child.board().hash()) >= 3 {
+            0
+        } else {
+            let dtz = -dtz(tablebases, child.board())?;
+            dtz + dtz.signum()
+        };
+
+        let dtz = if dtz == 2 && is_checkmate(child.board()) { 1 } else { dtz };
+        ranked.push((mv, dtz));
+    }
+
+    let best_win = ranked.iter().map(|&(_, dtz)| dtz).filter(|&dtz| dtz > 0).min();
+    let longest_loss = ranked.iter().map(|&(_, dtz)| dtz).min().unwrap_or(0);
+
+    let keep = |dtz: i32| {
+        if let Some(best) = best_win {
+            let max = if !repeated && best + half_moves <= 99 { 99 - half_moves } else { best };
+            dtz > 0 && dtz <= max
+        } else if longest_loss < 0 && ranked.iter().all(|&(_, dtz)| dtz < 0) {
+            -longest_loss * 2 + half_moves < 100 || dtz == longest_loss
+        } else {
+            dtz == 0
+        }
+    };
+
+    Some(ranked.iter().filter(|&&(_, dtz)| keep(dtz)).map(|&(mv, _)| mv).collect())
+}
+
+fn probeable_tablebases(board: &ChessBoard) -> Option<&'static Tablebases> {
+    let tablebases = current_tablebases()?;
+
+    let probeable = board.occupancy().pop_count() as usize <= tablebases.max_pieces() && !board.castle_rights().has_right(0b1111);
+    probeable.then_some(tablebases)
+}
+
+//Best result over zeroing moves (captures, and pawn moves when check_zeroing is set) and the table
+//value. The flag is set when a zeroing move is best, DTZ tables store nothing useful in that case.
+fn search(tablebases: &Tablebases, board: &ChessBoard, check_zeroing: bool) -> Option<(TablebaseWDL, bool)> {
+    let mask = board.castle_rights().get_castle_mask();
+
+    let mut moves = Vec::new();
+    board.map_legal_moves(|mv| moves.push(mv));
+
+    let mut best = TablebaseWDL::Loss;
+    let mut move_count = 0;
+
+    for &mv in moves.iter() {
+        if !mv.is_capture() && (!check_zeroing || board.piece_on_square(mv.get_from_square()) != Piece::PAWN) {
+            continue;
+        }
+
+        move_count += 1;
+
+        let mut child = *board;
+        child.make_move(mv, &mask);
+
+        let value = search(tablebases, &child, false)?.0.reversed();
+        if value > best {
+            best = value;
+
+            if value == TablebaseWDL::Win {
+                return Some((value, true));
+            }
+        }
+    }
+
+    //With every legal move searched the stored value can be wrong, e.g. with en passant rights
+    let no_more_moves = move_count > 0 && move_count == moves.len();
+
+    let value = if no_more_moves {
+        best
+    } else {
+        probe_wdl_table(tablebases, board)?
+    };
+
+    if best >= value {
+        return Some((best, best > TablebaseWDL::Draw || no_more_moves));
+    }
+
+    Some((value, false))
+}
+
+fn dtz(tablebases: &Tablebases, board: &ChessBoard) -> Option<i32> {
+    let (wdl, zeroing) = search(tablebases, board, true)?;
+
+    if wdl == TablebaseWDL::Draw {
+        return Some(0);
+    }
+
+    if zeroing {
+        return Some(dtz_before_zeroing(wdl));
+    }
+
+    match probe_dtz_table(tablebases, board, wdl)? {
+        TableProbe::Value(dtz) => {
+            let cursed = wdl == TablebaseWDL::CursedWin || wdl == TablebaseWDL::BlessedLoss;
+            Some((dtz + if cursed { 100 } else { 0 }) * wdl.value().signum())
+        }
+        TableProbe::ChangeSide => {
+            //The table stores the other side to move, find the best move with a 1-ply search
+            let mask = board.castle_rights().get_castle_mask();
+
+            let mut moves = Vec::new();
+            board.map_legal_moves(|mv| moves.push(mv));
+
+            let mut min_dtz = i32::MAX;
+            for mv in moves {
+                let zeroing = mv.is_capture() || board.piece_on_square(mv.get_from_square()) == Piece::PAWN;
+
+                let mut child = *board;
+                child.make_move(mv, &mask);
+
+                let mut dtz = if zeroing {
+                    -dtz_before_zeroing(search(tablebases, &child, false)?.0)
+                } else {
+                    -dtz(tablebases, &child)?
+                };
+
+                if dtz == 1 && is_checkmate(&child) {
+                    min_dtz = 1;
+                }
+
+                if !zeroing {
+                    dtz += dtz.signum();
+                }
+
+                if dtz < min_dtz && dtz.signum() == wdl.value().signum() {
+                    min_dtz = dtz;
+                }
+            }
+
+            Some(if min_dtz == i32::MAX { -1 } else { min_dtz })
+        }
+    }
+}
+
+fn dtz_before_zeroing(wdl: TablebaseWDL) -> i32 {
+    match wdl {
+        TablebaseWDL::Win => 1,
+        TablebaseWDL::CursedWin => 101,
+        TablebaseWDL::Draw => 0,
+        TablebaseWDL::BlessedLoss => -101,
+        TablebaseWDL::Loss => -1,
+    }
+}
+
+fn probe_wdl_table(tablebases: &Tablebases, board: &ChessBoard) -> Option<TablebaseWDL> {
+    if board.occupancy().pop_count() == 2 {
+        return Some(TablebaseWDL::Draw);
+    }
+
+    let key = material_key(board);
+    match tablebases.table(key, TableType::Wdl)?.probe(board, key, 0)? {
+        TableProbe::Value(value) => Some(TablebaseWDL::from_value(value)),
+        TableProbe::ChangeSide => None,
+    }
+}
+
+fn probe_dtz_table(tablebases: &Tablebases, board: &ChessBoard, wdl: TablebaseWDL) -> Option<TableProbe> {
+    let key = material_key(board);
+    tablebases.table(key, TableType::Dtz)?.probe(board, key, wdl.value())
+}
+
+fn is_checkmate(board: &ChessBoard) -> bool {
+    let mut has_moves = false;
+    board.map_legal_moves(|_| has_moves = true);
+    board.is_in_check() && !has_moves
+}
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        OnceLock,
+    },
+};
+
+use crate::syzygy::{
+    material::Material,
+    table::{Table, TableType},
+};
+
+//-----------------------------------------------
+// Tablebases found in SyzygyPath. Null until a path is set. Tables are read on first
+// use and, like networks, replaced sets are never freed because search threads can
+// still be probing them.
+//-----------------------------------------------
+
+static CURRENT_TABLEBASES: AtomicPtr<Tablebases> = AtomicPtr::new(std::ptr::null_mut());
+
+#[derive(Debug)]
+pub(super) struct Tablebases {
+    entries: Vec<TableEntry>,
+    keys: HashMap<u64, usize>,
+    max_pieces: usize,
+}
+
+#[derive(Debug)]
+struct TableEntry {
+    material: Material,
+    wdl_path: PathBuf,
+    dtz_path: Option<PathBuf>,
+    wdl: OnceLock<Option<Table>>,
+    dtz: OnceLock<Option<Table>>,
+}
+
+#[inline]
+pub(super) fn current_tablebases() -> Option<&'static Tablebases> {
+    unsafe { CURRENT_TABLEBASES.load(Ordering::Relaxed).as_ref() }
+}
+
+//Largest piece count with a WDL table, 0 when no tables are loaded.
+pub fn syzygy_max_pieces() -> usize {
+    current_tablebases().map_or(0, Tablebases::max_pieces)
+}
+
+//Accepts several directories separated by ':' (';' on Windows). Empty path disables probing,
+//on error probing stays disabled.
+pub fn load_syzygy(path: &str) -> Result<(), String> {
+    CURRENT_TABLEBASES.store(std::ptr::null_mut(), Ordering::Relaxed);
+
+    if path.is_empty() || path == "<empty>" {
+        return Ok(());
+    }
+
+    let separator = if cfg!(windows) { ';' } else { ':' };
+
+    let mut wdl_files = Vec::new();
+    let mut dtz_files = HashMap::new();
+
+    for directory in path.split(separator).filter(|directory| !directory.is_empty()) {
+        let files = std::fs::read_dir(directory).map_err(|err| format!("Failed to read '{directory}': {err}"))?;
+
+        for file in files.flatten() {
+            let path = file.path();
+            let (Some(name), Some(extension)) = (path.file_stem().and_then(|name| name.to_str()), path.extension()) else {
+                continue;
+            };
+
+            if extension == "rtbw" {
+                wdl_files.push((name.to_string(), path.clone()));
+            } else if extension == "rtbz" {
+                dtz_files.insert(name.to_string(), path.clone());
+            }
+        }
+    }
+
+    let mut tablebases = Tablebases { entries: Vec::new(), keys: HashMap::new(), max_pieces: 0 };
+
+    for (name, wdl_path) in wdl_files {
+        let Some(material) = Material::from_name(&name) else {
+            continue;
+        };
+
+        if tablebases.keys.contains_key(&material.key) {
+            continue;
+        }
+
+        let idx = tablebases.entries.len();
+        tablebases.keys.insert(material.key, idx);
+        tablebases.keys.insert(material.key2, idx);
+        tablebases.max_pieces = tablebases.max_pieces.max(material.piece_count);
+
+        tablebases.entries.push(TableEntry {
+            dtz_path: dtz_files.remove(&name),
+            material,
+            wdl_path,
+            wdl: OnceLock::new(),
+            dtz: OnceLock::new(),
+        });
+    }
+
+    if tablebases.entries.is_empty() {
+        return Err(format!("No Syzygy tables found in '{path}'"));
+    }
+
+    CURRENT_TABLEBASES.store(Box::into_raw(Box::new(tablebases)), Ordering::Relaxed);
+    Ok(())
+}
+
+impl Tablebases {
+    #[inline]
+    pub fn max_pieces(&self) -> usize {
+        self.max_pieces
+    }
+
+    //Table for the material key, read from disk on first use. Unreadable or corrupted files are
+    //treated as missing.
+    pub fn table(&self, key: u64, table_type: TableType) -> Option<&Table> {
+        let entry = &self.entries[*self.keys.get(&key)?];
+
+        let (slot, path) = match table_type {
+            TableType::Wdl => (&entry.wdl, Some(&entry.wdl_path)),
+            TableType::Dtz => (&entry.dtz, entry.dtz_path.as_ref()),
+        };
+
+        slot.get_or_init(|| {
+            let bytes = std::fs::read(path?).ok()?;
+            Table::new(bytes, &entry.material, table_type).ok()
+        })
+        .as_ref()
+    }
+}
@@ -0,0 +1,591 @@
+use chess::{Bitboard, ChessBoard, Piece, Side};
+
+use crate::syzygy::{
+    encoding::{file, flip_file, flip_rank, off_a1h8, rank, ENCODING},
+    material::Material,
+};
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+//Per table flags stored in front of the sizes
+const STM_FLAG: u8 = 1;
+const MAPPED_FLAG: u8 = 2;
+const WIN_PLIES_FLAG: u8 = 4;
+const LOSS_PLIES_FLAG: u8 = 8;
+const WIDE_FLAG: u8 = 16;
+const SINGLE_VALUE_FLAG: u8 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum TableType {
+    Wdl,
+    Dtz,
+}
+
+pub(super) enum TableProbe {
+    Value(i32),
+    //DTZ tables only store one side to move, the other one needs a 1-ply search
+    ChangeSide,
+}
+
+//Decoding data of a single (side to move, leading pawn file) subtable. Every position is a
+//byte offset into the table file.
+#[derive(Debug, Default, Clone)]
+struct PairsData {
+    flags: u8,
+    pieces: [u8; 7],
+    group_len: [usize; 8],
+    group_idx: [u64; 8],
+    min_sym_len: u8,
+    sizeof_block: u64,
+    span: u64,
+    sparse_index: usize,
+    sparse_index_size: u64,
+    block_length: usize,
+    block_length_size: u64,
+    blocks_num: u64,
+    data: usize,
+    lowest_sym: usize,
+    base64: Vec<u64>,
+    symlen: Vec<u8>,
+    btree: usize,
+    map_idx: [usize; 4],
+}
+
+#[derive(Debug)]
+pub(super) struct Table {
+    bytes: Vec<u8>,
+    table_type: TableType,
+    material: Material,
+    pairs: Vec<PairsData>,
+    map: usize,
+}
+
+impl Table {
+    pub fn new(bytes: Vec<u8>, material: &Material, table_type: TableType) -> Result<Self, String> {
+        let magic = if table_type == TableType::Wdl { WDL_MAGIC } else { DTZ_MAGIC };
+        if bytes.len() < 5 || bytes[..4] != magic {
+            return Err(String::from("bad magic"));
+        }
+
+        let mut table = Self { bytes, table_type, material: material.clone(), pairs: vec![PairsData::default(); 8], map: 0 };
+        table.init().ok_or(String::from("truncated file"))?;
+
+        Ok(table)
+    }
+
+    fn init(&mut self) -> Option<()> {
+        let has_pawns = self.material.has_pawns;
+        if (self.bytes[4] & 2 != 0) != has_pawns {
+            return None;
+        }
+
+        let sides = if self.table_type == TableType::Wdl && self.material.key != self.material.key2 { 2 } else { 1 };
+        let max_file = if has_pawns { 4 } else { 1 };
+        let both_pawns = has_pawns && self.material.pawn_count[1] > 0;
+
+        let mut data = 5;
+        for file in 0..max_file {
+            let first = *self.bytes.get(data)?;
+            let second = if both_pawns { *self.bytes.get(data + 1)? } else { 0xFF };
+            let order = [[first & 0xF, second & 0xF], [first >> 4, second >> 4]];
+            data += 1 + usize::from(both_pawns);
+
+            for k in 0..self.material.piece_count {
+                let pieces = *self.bytes.get(data)?;
+                for side in 0..sides {
+                    self.pairs[side * 4 + file].pieces[k] = if side == 1 { pieces >> 4 } else { pieces & 0xF };
+                }
+                data += 1;
+            }
+
+            for (side, &order) in order.iter().enumerate().take(sides) {
+                self.set_groups(side * 4 + file, order, file);
+            }
+        }
+
+        data += data & 1;
+
+        for file in 0..max_file {
+            for side in 0..sides {
+                data = self.set_sizes(side * 4 + file, data)?;
+            }
+        }
+
+        if self.table_type == TableType::Dtz {
+            data = self.set_dtz_map(data, max_file)?;
+        }
+
+        for file in 0..max_file {
+            for side in 0..sides {
+                let pairs = &mut self.pairs[side * 4 + file];
+                pairs.sparse_index = data;
+                data += pairs.sparse_index_size as usize * 6;
+            }
+        }
+
+        for file in 0..max_file {
+            for side in 0..sides {
+                let pairs = &mut self.pairs[side * 4 + file];
+                pairs.block_length = data;
+                data += pairs.block_length_size as usize * 2;
+            }
+        }
+
+        for file in 0..max_file {
+            for side in 0..sides {
+                data = (data + 0x3F) & !0x3F;
+                let pairs = &mut self.pairs[side * 4 + file];
+                pairs.data = data;
+                data += (pairs.blocks_num * pairs.sizeof_block) as usize;
+            }
+        }
+
+        (data <= self.bytes.len()).then_some(())
+    }
+
+    //Pieces are encoded in groups of identical pieces, the leading group being either the
+    //leading pawns or the first 2 or 3 pieces. The order says in which order groups are multiplied.
+    fn set_groups(&mut self, pairs_idx: usize, order: [u8; 2], file: usize) {
+        let material = &self.material;
+        let pairs = &mut self.pairs[pairs_idx];
+
+        let mut n = 0;
+        let mut first_len: i32 = if material.has_pawns {
+            0
+        } else if material.has_unique_pieces {
+            3
+        } else {
+            2
+        };
+
+        pairs.group_len[n] = 1;
+        for idx in 1..material.piece_count {
+            first_len -= 1;
+            if first_len > 0 || pairs.pieces[idx] == pairs.pieces[idx - 1] {
+                pairs.group_len[n] += 1;
+            } else {
+                n += 1;
+                pairs.group_len[n] = 1;
+            }
+        }
+
+        n += 1;
+        pairs.group_len[n] = 0;
+
+        let both_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut next = if both_pawns { 2 } else { 1 };
+        let mut free_squares = 64 - pairs.group_len[0] - if both_pawns { pairs.group_len[1] } else { 0 };
+        let mut idx = 1u64;
+
+        let mut k = 0;
+        while next < n || k == usize::from(order[0]) || k == usize::from(order[1]) {
+            if k == usize::from(order[0]) {
+                pairs.group_idx[0] = idx;
+                idx *= if material.has_pawns {
+                    ENCODING.lead_pawns_size[pairs.group_len[0]][file]
+                } else if material.has_unique_pieces {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == usize::from(order[1]) {
+                pairs.group_idx[1] = idx;
+                idx *= ENCODING.binomial[pairs.group_len[1]][48 - pairs.group_len[0]];
+            } else {
+                pairs.group_idx[next] = idx;
+                idx *= ENCODING.binomial[pairs.group_len[next]][free_squares];
+                free_squares -= pairs.group_len[next];
+                next += 1;
+            }
+
+            k += 1;
+        }
+
+        pairs.group_idx[n] = idx;
+    }
+
+    fn set_sizes(&mut self, pairs_idx: usize, mut data: usize) -> Option<usize> {
+        let bytes = &self.bytes;
+        let pairs = &mut self.pairs[pairs_idx];
+
+        pairs.flags = *bytes.get(data)?;
+        data += 1;
+
+        if pairs.flags & SINGLE_VALUE_FLAG != 0 {
+            pairs.min_sym_len = *bytes.get(data)?;
+            return Some(data + 1);
+        }
+
+        let groups = pairs.group_len.iter().position(|&len| len == 0)?;
+        let table_size = pairs.group_idx[groups];
+
+        pairs.sizeof_block = 1u64.checked_shl(u32::from(*bytes.get(data)?))?;
+        pairs.span = 1u64.checked_shl(u32::from(*bytes.get(data + 1)?))?;
+        pairs.sparse_index_size = table_size.div_ceil(pairs.span);
+        let padding = u64::from(*bytes.get(data + 2)?);
+        pairs.blocks_num = u64::from(read_u32_le(bytes, data + 3)?);
+        pairs.block_length_size = pairs.blocks_num + padding;
+        data += 7;
+
+        let max_sym_len = *bytes.get(data)?;
+        pairs.min_sym_len = *bytes.get(data + 1)?;
+        data += 2;
+
+        if pairs.min_sym_len == 0 || max_sym_len < pairs.min_sym_len {
+            return None;
+        }
+
+        pairs.lowest_sym = data;
+
+        //Canonical Huffman code, longer symbols have lower values. base64[i] is the lowest
+        //code of length min_sym_len + i, left aligned to 64 bits.
+        let base_count = usize::from(max_sym_len - pairs.min_sym_len) + 1;
+        pairs.base64 = vec![0; base_count];
+        for idx in (0..base_count - 1).rev() {
+            let lowest = u64::from(read_u16_le(bytes, pairs.lowest_sym + idx * 2)?);
+            let next_lowest = u64::from(read_u16_le(bytes, pairs.lowest_sym + (idx + 1) * 2)?);
+            pairs.base64[idx] = pairs.base64[idx + 1].wrapping_add(lowest).wrapping_sub(next_lowest) / 2;
+        }
+
+        for (idx, base) in pairs.base64.iter_mut().enumerate() {
+            *base = base.checked_shl((64 - idx - usize::from(pairs.min_sym_len)) as u32).unwrap_or(0);
+        }
+
+        data += base_count * 2;
+
+        let symbol_count = usize::from(read_u16_le(bytes, data)?);
+        data += 2;
+
+        pairs.btree = data;
+        if data + symbol_count * 3 > bytes.len() {
+            return None;
+        }
+
+        //Symbols are built by recursive pairing, symlen + 1 is the amount of values a symbol expands into.
+        pairs.symlen = vec![0; symbol_count];
+        let mut visited = vec![false; symbol_count];
+        for symbol in 0..symbol_count {
+            if !visited[symbol] {
+                pairs.symlen[symbol] = set_symlen(bytes, pairs.btree, &mut pairs.symlen, &mut visited, symbol)?;
+            }
+        }
+
+        Some(data + symbol_count * 3 + (symbol_count & 1))
+    }
+
+    fn set_dtz_map(&mut self, mut data: usize, max_file: usize) -> Option<usize> {
+        self.map = data;
+
+        for file in 0..max_file {
+            let flags = self.pairs[file].flags;
+            if flags & MAPPED_FLAG == 0 {
+                continue;
+            }
+
+            if flags & WIDE_FLAG != 0 {
+                data += data & 1;
+                for idx in 0..4 {
+                    self.pairs[file].map_idx[idx] = (data - self.map) / 2 + 1;
+                    data += 2 * usize::from(read_u16_le(&self.bytes, data)?) + 2;
+                }
+            } else {
+                for idx in 0..4 {
+                    self.pairs[file].map_idx[idx] = data - self.map + 1;
+                    data += usize::from(*self.bytes.get(data)?) + 1;
+                }
+            }
+        }
+
+        Some(data + (data & 1))
+    }
+
+    //Value stored for the position, wdl is needed to decode DTZ values.
+    pub fn probe(&self, board: &ChessBoard, material_key: u64, wdl: i32) -> Option<TableProbe> {
+        let encoding = &*ENCODING;
+        let material = &self.material;
+
+        //Tables are stored with white as the stronger side and symmetric ones only with white to move
+        let symmetric_black_to_move = material.key == material.key2 && board.side() == Side::BLACK;
+        let black_stronger = material_key != material.key;
+        let flip = symmetric_black_to_move || black_stronger;
+
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_squares = if flip { 56 } else { 0 };
+        let stm = usize::from(flip) ^ usize::from(board.side().get_value());
+
+        let mut squares = [0u8; 7];
+        let mut pieces = [0u8; 7];
+        let mut size = 0;
+        let mut lead_pawns = Bitboard::EMPTY;
+        let mut table_file = 0;
+
+        if material.has_pawns {
+            let lead_color = (self.pairs[0].pieces[0] ^ flip_color) >> 3;
+            lead_pawns = board.piece_mask_for_side(Piece::PAWN, Side::from(lead_color));
+
+            lead_pawns.map(|square| {
+                squares[size] = square.get_value() ^ flip_squares;
+                size += 1;
+            });
+
+            let lead_idx = (0..size).max_by_key(|&idx| encoding.map_pawns[usize::from(squares[idx])])?;
+            squares.swap(0, lead_idx);
+
+            table_file = usize::from(file(squares[0]).min(7 - file(squares[0])));
+        }
+
+        let lead_pawns_count = size;
+
+        if self.table_type == TableType::Dtz {
+            let flags = self.pairs[table_file].flags;
+            let symmetric = material.key == material.key2 && !material.has_pawns;
+            if usize::from(flags & STM_FLAG) != stm && !symmetric {
+                return Some(TableProbe::ChangeSide);
+            }
+        }
+
+        (board.occupancy() & !lead_pawns).map(|square| {
+            let piece = u8::from(board.piece_on_square(square)) + 1 + 8 * board.color_on_square(square).get_value();
+            squares[size] = square.get_value() ^ flip_squares;
+            pieces[size] = piece ^ flip_color;
+            size += 1;
+        });
+
+        let pairs = &self.pairs[if self.table_type == TableType::Wdl { stm * 4 } else { 0 } + table_file];
+
+        //Reorder pieces into the sequence the table was encoded with
+        for idx in lead_pawns_count..size.saturating_sub(1) {
+            if let Some(other) = (idx + 1..size).find(|&other| pairs.pieces[idx] == pieces[other]) {
+                pieces.swap(idx, other);
+                squares.swap(idx, other);
+            }
+        }
+
+        if file(squares[0]) > 3 {
+            squares[..size].iter_mut().for_each(|square| *square = flip_file(*square));
+        }
+
+        let mut idx = if material.has_pawns {
+            let mut idx = encoding.lead_pawn_idx[lead_pawns_count][usize::from(squares[0])];
+
+            squares[1..lead_pawns_count].sort_by_key(|&square| encoding.map_pawns[usize::from(square)]);
+            for (pawn, &square) in squares[1..lead_pawns_count].iter().enumerate() {
+                idx += encoding.binomial[pawn + 1][encoding.map_pawns[usize::from(square)] as usize];
+            }
+
+            idx
+        } else {
+            if rank(squares[0]) > 3 {
+                squares[..size].iter_mut().for_each(|square| *square = flip_rank(*square));
+            }
+
+            //Mirror along the a1-h8 diagonal so the first piece off the diagonal is below it
+            if let Some(first) = (0..pairs.group_len[0]).find(|&idx| off_a1h8(squares[idx]) != 0) {
+                if off_a1h8(squares[first]) > 0 {
+                    squares[first..size].iter_mut().for_each(|square| *square = ((*square >> 3) | (*square << 3)) & 63);
+                }
+            }
+
+            if material.has_unique_pieces {
+                encode_unique_pieces(&squares)
+            } else {
+                encoding.map_kk[encoding.map_a1d1d4[usize::from(squares[0])] as usize][usize::from(squares[1])]
+            }
+        };
+
+        idx *= pairs.group_idx[0];
+
+        let mut remaining_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut group_start = pairs.group_len[0];
+        let mut next = 1;
+
+        while pairs.group_len[next] != 0 {
+            let group_len = pairs.group_len[next];
+            squares[group_start..group_start + group_len].sort_unstable();
+
+            let mut n = 0;
+            for idx in 0..group_len {
+                let square = squares[group_start + idx];
+                let adjust = squares[..group_start].iter().filter(|&&other| square > other).count();
+                n += encoding.binomial[idx + 1][usize::from(square) - adjust - 8 * usize::from(remaining_pawns)];
+            }
+
+            remaining_pawns = false;
+            idx += n * pairs.group_idx[next];
+            group_start += group_len;
+            next += 1;
+        }
+
+        let value = self.decompress_pairs(pairs, idx)?;
+
+        Some(TableProbe::Value(match self.table_type {
+            TableType::Wdl => value - 2,
+            TableType::Dtz => self.map_dtz(pairs, value, wdl)?,
+        }))
+    }
+
+    fn map_dtz(&self, pairs: &PairsData, mut value: i32, wdl: i32) -> Option<i32> {
+        const WDL_MAP: [usize; 5] = [1, 3, 0, 2, 0];
+
+        if pairs.flags & MAPPED_FLAG != 0 {
+            let idx = pairs.map_idx[WDL_MAP[(wdl + 2) as usize]] + value as usize;
+            value = if pairs.flags & WIDE_FLAG != 0 {
+                i32::from(read_u16_le(&self.bytes, self.map + idx * 2)?)
+            } else {
+                i32::from(*self.bytes.get(self.map + idx)?)
+            };
+        }
+
+        //Values are stored in moves unless the plies flag is set, we always return plies
+        if (wdl == 2 && pairs.flags & WIN_PLIES_FLAG == 0) || (wdl == -2 && pairs.flags & LOSS_PLIES_FLAG == 0) || wdl == 1 || wdl == -1 {
+            value *= 2;
+        }
+
+        Some(value + 1)
+    }
+
+    fn decompress_pairs(&self, pairs: &PairsData, idx: u64) -> Option<i32> {
+        if pairs.flags & SINGLE_VALUE_FLAG != 0 {
+            return Some(i32::from(pairs.min_sym_len));
+        }
+
+        let bytes = &self.bytes;
+
+        //The sparse index points at a known value near idx, walk the block lengths from there
+        let k = (idx / pairs.span) as usize;
+        let mut block = read_u32_le(bytes, pairs.sparse_index + k * 6)? as usize;
+        let mut offset = i64::from(read_u16_le(bytes, pairs.sparse_index + k * 6 + 4)?);
+
+        offset += (idx % pairs.span) as i64 - (pairs.span / 2) as i64;
+
+        while offset < 0 {
+            block = block.checked_sub(1)?;
+            offset += i64::from(read_u16_le(bytes, pairs.block_length + block * 2)?) + 1;
+        }
+
+        loop {
+            let length = i64::from(read_u16_le(bytes, pairs.block_length + block * 2)?);
+            if offset <= length {
+                break;
+            }
+
+            offset -= length + 1;
+            block += 1;
+        }
+
+        let mut ptr = pairs.data + block * pairs.sizeof_block as usize;
+        let mut buffer = read_u64_be(bytes, ptr)?;
+        let mut buffer_size = 64;
+        ptr += 8;
+
+        let min_sym_len = usize::from(pairs.min_sym_len);
+        let mut symbol;
+
+        loop {
+            let mut len = 0;
+            while buffer < pairs.base64[len] {
+                len += 1;
+            }
+
+            symbol = ((buffer - pairs.base64[len]) >> (64 - len - min_sym_len)) as usize;
+            symbol += usize::from(read_u16_le(bytes, pairs.lowest_sym + len * 2)?);
+
+            let symbol_len = i64::from(*pairs.symlen.get(symbol)?);
+            if offset < symbol_len + 1 {
+                break;
+            }
+
+            offset -= symbol_len + 1;
+            len += min_sym_len;
+            buffer <<= len;
+            buffer_size -= len;
+
+            if buffer_size <= 32 {
+                buffer_size += 32;
+                buffer |= u64::from(read_u32_be(bytes, ptr)?) << (64 - buffer_size);
+                ptr += 4;
+            }
+        }
+
+        //Expand the symbol until we reach the single value at offset
+        while pairs.symlen[symbol] != 0 {
+            let (left, right) = read_pair(bytes, pairs.btree + symbol * 3)?;
+            let left_len = i64::from(*pairs.symlen.get(left)?);
+
+            if offset < left_len + 1 {
+                symbol = left;
+            } else {
+                offset -= left_len + 1;
+                symbol = right;
+            }
+        }
+
+        Some(read_pair(bytes, pairs.btree + symbol * 3)?.0 as i32)
+    }
+}
+
+fn set_symlen(bytes: &[u8], btree: usize, symlen: &mut [u8], visited: &mut [bool], symbol: usize) -> Option<u8> {
+    visited[symbol] = true;
+
+    let (left, right) = read_pair(bytes, btree + symbol * 3)?;
+    if right == 0xFFF {
+        return Some(0);
+    }
+
+    for child in [left, right] {
+        if !*visited.get(child)? {
+            symlen[child] = set_symlen(bytes, btree, symlen, visited, child)?;
+        }
+    }
+
+    Some(symlen[left].wrapping_add(symlen[right]).wrapping_add(1))
+}
+
+//Leading group of 3 unique pieces, the first one is in the a1-d1-d4 triangle
+fn encode_unique_pieces(squares: &[u8; 7]) -> u64 {
+    let encoding = &*ENCODING;
+    let [first, second, third] = [squares[0], squares[1], squares[2]].map(u64::from);
+
+    let adjust1 = u64::from(second > first);
+    let adjust2 = u64::from(third > first) + u64::from(third > second);
+
+    if off_a1h8(squares[0]) != 0 {
+        (encoding.map_a1d1d4[first as usize] * 63 + (second - adjust1)) * 62 + third - adjust2
+    } else if off_a1h8(squares[1]) != 0 {
+        (6 * 63 + u64::from(rank(squares[0])) * 28 + encoding.map_b1h1h7[second as usize]) * 62 + third - adjust2
+    } else if off_a1h8(squares[2]) != 0 {
+        6 * 63 * 62 + 4 * 28 * 62 + u64::from(rank(squares[0])) * 7 * 28 + (u64::from(rank(squares[1])) - adjust1) * 28 + encoding.map_b1h1h7[third as usize]
+    } else {
+        6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + u64::from(rank(squares[0])) * 7 * 6 + (u64::from(rank(squares[1])) - adjust1) * 6 + (u64::from(rank(squares[2])) - adjust2)
+    }
+}
+
+//12 bit left and right symbols of a pair
+#[inline]
+fn read_pair(bytes: &[u8], offset: usize) -> Option<(usize, usize)> {
+    let pair = bytes.get(offset..offset + 3)?;
+    let left = (usize::from(pair[1] & 0xF) << 8) | usize::from(pair[0]);
+    let right = (usize::from(pair[2]) << 4) | usize::from(pair[1] >> 4);
+    Some((left, right))
+}
+
+#[inline]
+fn read_u16_le(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+#[inline]
+fn read_u64_be(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+}
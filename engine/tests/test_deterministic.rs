@@ -0,0 +1,100 @@
+use chess::{ChessBoard, ChessPosition, Move, FEN};
+use engine::{NoReport, NodeIndex, SearchEngine, SearchLimits, SearchReport, SearchStats, Tree};
+
+fn collect_tree(tree: &Tree, node_idx: NodeIndex, nodes: &mut Vec<(String, u32, u64, u64)>) {
+    let node = &tree[node_idx];
+    let score = node.score();
+    nodes.push((node.mv().to_string(false), node.visits(), score.win_chance().to_bits(), score.draw_chance().to_bits()));
+
+    node.map_children(|child_idx| collect_tree(tree, child_idx, nodes));
+}
+
+fn search_tree(fen: &str, threads: &str) -> (Vec<(String, u32, u64, u64)>, u64) {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("Deterministic", "true").unwrap();
+    let _ = search_engine.set_option("Threads", threads);
+    search_engine.set_position(&ChessPosition::from(ChessBoard::from(&FEN::from(fen))), 0);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(3000));
+
+    let result = search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let mut nodes = Vec::new();
+    collect_tree(search_engine.tree(), search_engine.tree().root_index(), &mut nodes);
+    (nodes, result.iterations())
+}
+
+#[test]
+fn identical_trees() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/2pb1ppp/2pp1q2/p7/1nP1B3/1P2P3/P2N1PPP/R2QK2R w KQkq a6 0 14",
+    ];
+
+    for fen in fens {
+        for threads in ["1", "4"] {
+            let (tree, iterations) = search_tree(fen, threads);
+            for _ in 0..3 {
+                assert_eq!(search_tree(fen, threads), (tree.clone(), iterations));
+            }
+        }
+    }
+}
+
+#[test]
+fn bench_signature() {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("Deterministic", "true").unwrap();
+    search_engine.set_option("Threads", "3").unwrap();
+
+    let (nodes, _) = search_engine.bench(Some(2));
+    assert_eq!(search_engine.bench(Some(2)).0, nodes);
+}
+
+#[test]
+fn small_tree_swaps() {
+    let run = || {
+        let mut search_engine = SearchEngine::new();
+        search_engine.set_option("Deterministic", "true").unwrap();
+        search_engine.set_option("Threads", "3").unwrap();
+        search_engine.set_option("Hash", "1").unwrap();
+        search_engine.resize_tree();
+
+        //Running out of nodes ends the round for every thread before the halves are swapped
+        let mut limits = SearchLimits::default();
+        limits.set_iters(Some(50_000));
+
+        let result = search_engine.search(&limits, &mut NoReport).unwrap();
+        (result.iterations(), result.best_move(), search_engine.tree().root_node().visits())
+    };
+
+    assert_eq!(run(), run());
+}
+
+struct PanickingReport;
+impl SearchReport for PanickingReport {
+    fn refresh_rate_per_second(&self) -> f64 {
+        1.0
+    }
+
+    fn best_move_changed(&mut self, _: &SearchLimits, _: &SearchStats, _: Move, _: &SearchEngine) {
+        panic!("report failed");
+    }
+}
+
+#[test]
+fn panic_releases_round() {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("Deterministic", "true").unwrap();
+    search_engine.set_option("Threads", "4").unwrap();
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(3000));
+
+    //The main thread panics between two rounds, the workers waiting for it have to be let go
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| search_engine.search(&limits, &mut PanickingReport)));
+    assert!(result.is_err());
+
+    assert_eq!(search_engine.search(&limits, &mut NoReport).unwrap().iterations(), 3000);
+}
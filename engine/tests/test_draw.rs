@@ -1,5 +1,5 @@
 use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Square, FEN};
-use engine::{NoReport, SearchEngine, SearchLimits};
+use engine::{GameState, NoReport, SearchEngine, SearchLimits};
 
 #[test]
 fn three_fold() { 
@@ -17,7 +17,7 @@ fn three_fold() {
     let mut limits = SearchLimits::default();
     limits.set_iters(Some(20000));
 
-    search_engine.search::<NoReport>(&limits);
+    search_engine.search(&limits, &mut NoReport).unwrap();
 
     let best_move = search_engine.tree().get_best_pv(0, 0.5).first_move();
     assert_eq!(best_move, Move::from_squares(Square::C8, Square::H3, MoveFlag::QUIET_MOVE))
@@ -34,7 +34,7 @@ fn fifty_mr() {
     let mut limits = SearchLimits::default();
     limits.set_iters(Some(2000));
 
-    search_engine.search::<NoReport>(&limits);
+    search_engine.search(&limits, &mut NoReport).unwrap();
 
     let draw_distance = 0.5 - search_engine.tree().get_best_pv(0, 0.5).score().single();
     assert!(draw_distance.abs() < 0.1)
@@ -51,8 +51,43 @@ fn fifty_mr_mate() {
     let mut limits = SearchLimits::default();
     limits.set_iters(Some(2000));
 
-    search_engine.search::<NoReport>(&limits);
+    search_engine.search(&limits, &mut NoReport).unwrap();
 
     let draw_distance = 0.5 - search_engine.tree().get_best_pv(0, 0.5).score().single();
     assert!(draw_distance.abs() > 0.4)
-}
\ No newline at end of file
+}
+
+#[test]
+fn proven_draw() { 
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("k7/Q7/8/8/8/8/8/7K b - - 0 1")));
+
+    search_engine.set_position(&position, 0);
+    
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(100));
+
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    assert_eq!(search_engine.tree().root_node().state(), GameState::Draw);
+}
+
+#[test]
+fn proven_draw_over_loss() { 
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("7k/8/6K1/8/8/2p5/8/1R6 b - - 99 80")));
+
+    search_engine.set_position(&position, 0);
+    
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(2000));
+
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let pv = search_engine.tree().get_best_pv(0, 0.5);
+    assert_eq!(search_engine.tree().root_node().state(), GameState::Draw);
+    assert_eq!(pv.first_node().state(), GameState::Draw);
+    assert_eq!(pv.first_move(), Move::from_squares(Square::H8, Square::G8, MoveFlag::QUIET_MOVE))
+}
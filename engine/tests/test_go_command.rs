@@ -0,0 +1,73 @@
+use engine::GoCommand;
+
+fn parse(line: &str) -> (GoCommand, Vec<String>) {
+    let args = line.split_whitespace().skip(1).map(String::from).collect::<Vec<_>>();
+    GoCommand::parse(&args)
+}
+
+#[test]
+fn gui_command_lines() {
+    let (go, diagnostics) = parse("go wtime 300000 btime 298000 winc 2000 binc 2000");
+    assert!(diagnostics.is_empty());
+    assert_eq!((go.wtime(), go.btime(), go.winc(), go.binc()), (Some(300000), Some(298000), Some(2000), Some(2000)));
+    assert_eq!(go.moves_to_go(), None);
+
+    let (go, diagnostics) = parse("go wtime 60000 btime 60000 movestogo 40");
+    assert!(diagnostics.is_empty());
+    assert_eq!(go.moves_to_go(), Some(40));
+
+    let (go, diagnostics) = parse("go ponder wtime 1000 btime 1000 winc 100 binc 100");
+    assert!(diagnostics.is_empty());
+    assert!(go.ponder());
+
+    let (go, diagnostics) = parse("go searchmoves e2e4 d2d4 g1f3 infinite");
+    assert!(diagnostics.is_empty());
+    assert_eq!(go.search_moves(), ["e2e4", "d2d4", "g1f3"]);
+    assert!(go.infinite());
+
+    let (go, diagnostics) = parse("go nodes 5000 depth 12 mate 3 movetime 250");
+    assert!(diagnostics.is_empty());
+    assert_eq!((go.nodes(), go.depth(), go.mate(), go.move_time()), (Some(5000), Some(12), Some(3), Some(250)));
+
+    //Clocks below zero are sent by some GUIs once a side runs out of time
+    let (go, diagnostics) = parse("go wtime -35 btime 1200");
+    assert!(diagnostics.is_empty());
+    assert_eq!(go.wtime(), Some(0));
+}
+
+#[test]
+fn round_trip() {
+    let lines = [
+        "go",
+        "go infinite",
+        "go wtime 300000 btime 298000 winc 2000 binc 2000",
+        "go searchmoves e2e4 d2d4 ponder wtime 1000 btime 1000 movestogo 12",
+        "go depth 12 nodes 5000 mate 3 movetime 250",
+        "go searchmoves e7e8q infinite",
+    ];
+
+    for line in lines {
+        let (go, diagnostics) = parse(line);
+        assert!(diagnostics.is_empty());
+        assert_eq!(go.to_string(), line);
+        assert_eq!(parse(&go.to_string()).0, go);
+    }
+}
+
+#[test]
+fn diagnostics() {
+    let (go, diagnostics) = parse("go wtime abc btime 1000 moves_to_go 5 depth");
+    assert_eq!(go.wtime(), None);
+    assert_eq!(go.btime(), Some(1000));
+    assert_eq!(go.depth(), None);
+    assert_eq!(diagnostics, [
+        "Invalid value 'abc' for 'wtime'",
+        "Unknown go parameter 'moves_to_go'",
+        "Unknown go parameter '5'",
+        "Missing value for 'depth'",
+    ]);
+
+    let (go, diagnostics) = parse("go movetime infinite");
+    assert!(go.infinite());
+    assert_eq!(diagnostics, ["Missing value for 'movetime'"]);
+}
@@ -0,0 +1,60 @@
+use chess::{ChessBoard, FEN};
+use engine::{NoReport, NodeIndex, SearchEngine, SearchLimits, Tree, WDLScore};
+
+#[test]
+fn hash_table_entries() {
+    let tree = Tree::from_bytes(4, 0.05);
+    let table = tree.hash_table();
+
+    let start = ChessBoard::from(&FEN::start_position()).hash();
+    let kiwipete = ChessBoard::from(&FEN::kiwipete_position()).hash();
+
+    assert_eq!(table.get(start), None);
+
+    table.push(start, WDLScore::new(0.5, 0.25), 10);
+    table.push(kiwipete, WDLScore::DRAW, 1);
+    assert_eq!(table.get(start).map(|(_, visits)| visits), Some(10));
+    assert_eq!(table.get(kiwipete).map(|(_, visits)| visits), Some(1));
+
+    //A shallower search of the same position doesn't overwrite a deeper one
+    table.push(start, WDLScore::WIN, 3);
+    assert_eq!(table.get(start).map(|(_, visits)| visits), Some(10));
+
+    //Unless it comes from an earlier search
+    table.increment_age();
+    table.push(start, WDLScore::WIN, 3);
+    assert_eq!(table.get(start), Some((WDLScore::WIN, 3)));
+
+    //Children blocks belong to one tree generation and to the position that stored them
+    let children_idx = NodeIndex::new(1, 20);
+    table.set_children(start, children_idx, 2);
+    table.set_children(kiwipete, children_idx, 2);
+    assert_eq!(table.children(start, 2), Some(children_idx));
+    assert_eq!(table.children(start, 3), None);
+
+    let other = ChessBoard::from(&FEN::from("8/8/8/8/8/8/8/K6k w - - 0 1")).hash();
+    assert_eq!(table.children(other, 2), None);
+    table.set_children(other, children_idx, 2);
+    assert_eq!(table.children(other, 2), None);
+
+    tree.clear();
+    assert_eq!(table.get(start), None);
+    assert_eq!(table.hashfull(), 0);
+}
+
+#[test]
+fn hashfull() {
+    let search_engine = SearchEngine::new();
+    assert_eq!(search_engine.tree().hash_table().hashfull(), 0);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(20000));
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let hashfull = search_engine.tree().hash_table().hashfull();
+    assert!(hashfull > 0 && hashfull <= 1000);
+
+    //Entries of a finished search don't count as full anymore
+    search_engine.tree().hash_table().increment_age();
+    assert_eq!(search_engine.tree().hash_table().hashfull(), 0);
+}
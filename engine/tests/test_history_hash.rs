@@ -97,4 +97,32 @@ fn irreversable_transposition() {
     let hash_b = position.history().hash();
 
     assert_eq!(hash_a, hash_b);
-}
\ No newline at end of file
+}
+#[test]
+fn repetition_key() {
+    let moves = [
+        Move::from_squares(Square::G1, Square::F3, MoveFlag::QUIET_MOVE),
+        Move::from_squares(Square::G8, Square::F6, MoveFlag::QUIET_MOVE),
+        Move::from_squares(Square::F3, Square::G1, MoveFlag::QUIET_MOVE),
+        Move::from_squares(Square::F6, Square::G8, MoveFlag::QUIET_MOVE),
+    ];
+
+    let start = ChessPosition::from(ChessBoard::from(&FEN::from("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")));
+    assert_eq!(start.repetition_key(), start.board().hash());
+
+    let mut position = start;
+    for mv in moves {
+        position.make_move_no_mask(mv);
+    }
+
+    //Same board, but a repetition of it can't share statistics with the first occurrence
+    assert_eq!(position.board().hash(), start.board().hash());
+    assert_ne!(position.repetition_key(), start.repetition_key());
+
+    let mut repeated_twice = position;
+    for mv in moves {
+        repeated_twice.make_move_no_mask(mv);
+    }
+
+    assert_ne!(repeated_twice.repetition_key(), position.repetition_key());
+}
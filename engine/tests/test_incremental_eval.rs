@@ -0,0 +1,64 @@
+use chess::{ChessBoard, FEN};
+use engine::{load_value_network, AccumulatorStack, value_network, NetworkHeader, ValueNetwork};
+use utils::Random;
+
+#[test]
+fn incremental_matches_full_refresh() {
+    let mut random = Random::new(0);
+
+    //Small weights keep every accumulator sum inside i16, so both paths must agree exactly.
+    let mut payload = Vec::with_capacity(std::mem::size_of_val(&ValueNetwork));
+    while payload.len() < std::mem::size_of_val(&ValueNetwork) {
+        payload.extend_from_slice(&(random.range(129) as i16 - 64).to_le_bytes());
+    }
+
+    let path = std::env::temp_dir().join("jackal_test_incremental.network");
+    let mut bytes = NetworkHeader::value(&payload).to_bytes().to_vec();
+    bytes.extend_from_slice(&payload);
+    std::fs::write(&path, &bytes).unwrap();
+    load_value_network(path.to_str().unwrap()).unwrap();
+
+    let fens = [
+        FEN::start_position(),
+        FEN::kiwipete_position(),
+        FEN::from("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1"),
+        FEN::from("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"),
+    ];
+
+    let mut stack = AccumulatorStack::default();
+
+    for fen in fens {
+        for _ in 0..8 {
+            //Paths restart from a random earlier ply, like selection paths branching off from the last one
+            let mut path = vec![ChessBoard::from(&fen)];
+
+            for _ in 0..40 {
+                let ply = random.range(path.len());
+                path.truncate(ply + 1);
+
+                stack.reset(&path[0]);
+                path[1..].iter().for_each(|board| stack.push(board));
+
+                for _ in 0..4 {
+                    let board = *path.last().unwrap();
+                    assert_eq!(value_network().forward(&board), value_network().forward_stack(&mut stack));
+
+                    let mut moves = Vec::new();
+                    board.map_legal_moves(|mv| moves.push(mv));
+
+                    if moves.is_empty() {
+                        break;
+                    }
+
+                    let mut new_board = board;
+                    new_board.make_move_no_mask(moves[random.range(moves.len())]);
+                    stack.push(&new_board);
+                    path.push(new_board);
+                }
+            }
+        }
+    }
+
+    load_value_network("").unwrap();
+    let _ = std::fs::remove_file(&path);
+}
@@ -1,5 +1,5 @@
 use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Square, FEN};
-use engine::{NoReport, SearchEngine, SearchLimits};
+use engine::{GameState, NoReport, SearchEngine, SearchLimits};
 
 #[test]
 fn mate_in_1() { 
@@ -12,7 +12,7 @@ fn mate_in_1() {
     let mut limits = SearchLimits::default();
     limits.set_iters(Some(2000));
 
-    search_engine.search::<NoReport>(&limits);
+    search_engine.search(&limits, &mut NoReport).unwrap();
 
     let best_move = search_engine.tree().get_best_pv(0, search_engine.options().draw_score() as f64 / 100.0).first_move();
     assert_eq!(best_move, Move::from_squares(Square::B8, Square::A8, MoveFlag::QUIET_MOVE))
@@ -29,8 +29,28 @@ fn mate_in_2() {
     let mut limits = SearchLimits::default();
     limits.set_iters(Some(125000));
 
-    search_engine.search::<NoReport>(&limits);
+    search_engine.search(&limits, &mut NoReport).unwrap();
 
     let best_move = search_engine.tree().get_best_pv(0, search_engine.options().draw_score() as f64 / 100.0).first_move();
     assert_eq!(best_move, Move::from_squares(Square::D5, Square::D8, MoveFlag::QUIET_MOVE))
-}
\ No newline at end of file
+}
+
+#[test]
+fn go_mate() { 
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("r1b2k1r/ppp1bppp/8/1B1Q4/5q2/2P5/PPP2PPP/R3R1K1 w - - 1 1")));
+
+    search_engine.set_position(&position, 0);
+    
+    let mut limits = SearchLimits::default();
+    limits.set_mate(Some(2));
+    limits.set_iters(Some(1_000_000));
+
+    let stats = search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let pv = search_engine.tree().get_best_pv(0, search_engine.options().draw_score() as f64 / 100.0);
+    assert!(stats.iterations() < 1_000_000);
+    assert!(matches!(search_engine.tree().root_node().state(), GameState::Win(len) if len <= 3));
+    assert_eq!(pv.first_move(), Move::from_squares(Square::D5, Square::D8, MoveFlag::QUIET_MOVE))
+}
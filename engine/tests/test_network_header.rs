@@ -0,0 +1,90 @@
+use engine::{load_policy_network, load_value_network, policy_network, quantise_policy_file, NetworkHeader, NoReport, PolicyNetwork, SearchEngine, SearchLimits};
+
+#[test]
+fn header_round_trip() {
+    let payload = [7u8; 256];
+    let header = NetworkHeader::policy(&payload);
+
+    assert_eq!(NetworkHeader::from_bytes(&header.to_bytes()), Ok(header));
+    assert_eq!(header.payload_size(), payload.len());
+    assert!(header.validate(&NetworkHeader::policy(&payload)).is_ok());
+}
+
+#[test]
+fn header_mismatch() {
+    let payload = [7u8; 256];
+    let header = NetworkHeader::policy(&payload);
+
+    assert!(header.validate(&NetworkHeader::value(&payload)).is_err());
+    assert!(header.validate(&NetworkHeader::policy(&payload[1..])).is_err());
+
+    let mut corrupted = payload;
+    corrupted[100] = 0;
+    assert!(header.validate(&NetworkHeader::policy(&corrupted)).is_err());
+
+    assert!(NetworkHeader::from_bytes(&payload).is_err());
+    assert!(NetworkHeader::from_bytes(&header.to_bytes()[..32]).is_err());
+}
+
+#[test]
+fn loader() {
+    let path = std::env::temp_dir().join("jackal_test_policy.network");
+    let path_str = path.to_str().unwrap();
+    let embedded = policy_network() as *const _;
+
+    let payload = vec![0u8; std::mem::size_of_val(&PolicyNetwork)];
+
+    std::fs::write(&path, &payload).unwrap();
+    assert!(load_policy_network(path_str).is_err());
+    assert_eq!(policy_network() as *const _, embedded);
+
+    let mut bytes = NetworkHeader::value(&payload).to_bytes().to_vec();
+    bytes.extend_from_slice(&payload);
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(load_policy_network(path_str).is_err());
+    assert!(load_value_network(path_str).is_err());
+    assert_eq!(policy_network() as *const _, embedded);
+
+    let mut bytes = NetworkHeader::policy(&payload).to_bytes().to_vec();
+    bytes.extend_from_slice(&payload);
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(load_policy_network(path_str).is_ok());
+    assert_ne!(policy_network() as *const _, embedded);
+
+    assert!(load_policy_network("").is_ok());
+    assert_eq!(policy_network() as *const _, embedded);
+
+    let quantised_path = std::env::temp_dir().join("jackal_test_quantised_policy.network");
+    let quantised_path_str = quantised_path.to_str().unwrap();
+
+    assert!(quantise_policy_file(path_str, quantised_path_str).is_ok());
+    assert!(quantise_policy_file(quantised_path_str, quantised_path_str).is_err());
+
+    let quantised = std::fs::read(&quantised_path).unwrap();
+    let header = NetworkHeader::from_bytes(&quantised).unwrap();
+    assert_eq!(header.architecture(), NetworkHeader::QUANTISED_POLICY_ARCHITECTURE);
+    assert!(quantised.len() < bytes.len());
+
+    assert!(load_policy_network(quantised_path_str).is_ok());
+    assert_ne!(policy_network() as *const _, embedded);
+
+    assert!(load_policy_network("").is_ok());
+    assert_eq!(policy_network() as *const _, embedded);
+
+    //Every reload through the engine frees the net it replaced, searches use the one just loaded
+    let mut search_engine = SearchEngine::new();
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(200));
+
+    for path in [path_str, quantised_path_str, path_str] {
+        assert!(search_engine.set_option("PolicyFile", path).is_ok());
+        assert_ne!(policy_network() as *const _, embedded);
+        search_engine.search(&limits, &mut NoReport).unwrap();
+    }
+
+    assert!(search_engine.set_option("PolicyFile", "").is_ok());
+    assert_eq!(policy_network() as *const _, embedded);
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&quantised_path);
+}
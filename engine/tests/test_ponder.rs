@@ -0,0 +1,48 @@
+use std::{thread, time::Duration};
+
+use engine::{NoReport, SearchEngine, SearchLimits};
+
+#[test]
+fn ponderhit() { 
+    let search_engine = SearchEngine::new();
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(1000));
+    limits.set_time(10);
+    limits.set_ponder(true);
+
+    let stats = thread::scope(|s| {
+        let handle = s.spawn(|| search_engine.search(&limits, &mut NoReport).unwrap());
+
+        //Neither the iteration nor the time limit applies while pondering
+        thread::sleep(Duration::from_millis(200));
+        assert!(!handle.is_finished());
+        assert!(search_engine.is_pondering());
+
+        search_engine.ponderhit();
+        handle.join().unwrap()
+    });
+
+    assert!(!search_engine.is_pondering());
+    assert!(stats.iterations() > 1000);
+    assert!(search_engine.tree().get_best_pv(0, 0.5).ponder_move().is_some());
+}
+
+#[test]
+fn early_ponderhit() { 
+    let search_engine = SearchEngine::new();
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(1000));
+    limits.set_ponder(true);
+
+    //The ponderhit can arrive before the search thread gets to run
+    let stats = thread::scope(|s| {
+        let handle = search_engine.spawn_search(s, &limits, NoReport).unwrap();
+        search_engine.ponderhit();
+        handle.join().unwrap()
+    });
+
+    assert!(!search_engine.is_pondering());
+    assert_eq!(stats.iterations(), 1000);
+}
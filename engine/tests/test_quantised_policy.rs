@@ -0,0 +1,103 @@
+use chess::{ChessBoard, FEN};
+use engine::{load_policy_network, policy_network, quantise_policy_file, NetworkHeader, PolicyCache, PolicyNetwork};
+
+//Float network with the layout of the embedded one
+fn float_network<'a, T>(_layout: &T, values: &'a [f32]) -> &'a T {
+    assert_eq!(std::mem::size_of_val(values), std::mem::size_of::<T>());
+    unsafe { &*(values.as_ptr() as *const T) }
+}
+
+//Fixed xorshift sequence in -0.5..0.5, so every run checks the same weights
+fn random_weights(count: usize) -> Vec<f32> {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    (0..count).map(|_| {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+    }).collect()
+}
+
+//Steps a subnet value is quantised with, l0 by QA, l1 weights by QB and l1 biases by QA * QB
+fn quantisation_scale(idx: usize) -> f32 {
+    const L1_WEIGHTS: usize = 768 * 32 + 32;
+    const L1_BIASES: usize = L1_WEIGHTS + 32 * 32;
+
+    match idx % (L1_BIASES + 32) {
+        idx if idx < L1_WEIGHTS => 255.0,
+        idx if idx < L1_BIASES => 64.0,
+        _ => 255.0 * 64.0,
+    }
+}
+
+fn write_network(name: &str, values: &[f32]) -> String {
+    let payload = values.iter().flat_map(|value| value.to_le_bytes()).collect::<Vec<u8>>();
+
+    let mut bytes = NetworkHeader::policy(&payload).to_bytes().to_vec();
+    bytes.extend_from_slice(&payload);
+
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, bytes).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn quantised_matches_float() {
+    let count = std::mem::size_of_val(&PolicyNetwork) / 4;
+    let offsets = random_weights(2 * count).split_off(count);
+
+    //The float reference sits on the quantisation steps, the loaded values are up to 0.4 steps off them.
+    //Rounding to the nearest step recovers the reference exactly, only f32 rounding of the sums is left.
+    let (reference, values): (Vec<f32>, Vec<f32>) = random_weights(count)
+        .iter()
+        .zip(&offsets)
+        .enumerate()
+        .map(|(idx, (&weight, &offset))| {
+            let scale = quantisation_scale(idx);
+            let step = (weight * scale).round();
+            (step / scale, (step + 0.8 * offset) / scale)
+        })
+        .unzip();
+
+    let path = write_network("jackal_test_random_policy.network", &values);
+    assert!(load_policy_network(&path).is_ok());
+
+    let float = float_network(&PolicyNetwork, &reference);
+
+    for fen in [
+        FEN::start_position(),
+        FEN::from("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4"),
+        FEN::from("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1"),
+        FEN::from("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"),
+    ] {
+        let board = ChessBoard::from(&fen);
+        let inputs = policy_network().get_inputs(&board);
+        let mut cache = PolicyCache::default();
+
+        board.map_legal_moves(|mv| {
+            let expected = float.forward(&board, &inputs, mv);
+            let result = policy_network().forward(&board, &inputs, mv, &mut cache);
+
+            assert!((result - expected).abs() <= 1e-4 * (expected.abs() + 1.0), "{mv:?}: {result} != {expected}");
+        });
+    }
+
+    assert!(load_policy_network("").is_ok());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn quantise_fails_when_clamping() {
+    let mut values = vec![0.0f32; std::mem::size_of_val(&PolicyNetwork) / 4];
+    let last_l1_weight = values.len() - 100;
+    values[last_l1_weight] = 5.0;
+
+    let path = write_network("jackal_test_clamped_policy.network", &values);
+    let quantised_path = std::env::temp_dir().join("jackal_test_clamped_quantised_policy.network");
+
+    let err = quantise_policy_file(&path, quantised_path.to_str().unwrap()).unwrap_err();
+    assert!(err.contains("1 values outside"));
+    assert!(!quantised_path.exists());
+
+    let _ = std::fs::remove_file(&path);
+}
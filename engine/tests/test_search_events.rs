@@ -0,0 +1,38 @@
+use std::sync::mpsc;
+
+use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Square, FEN};
+use engine::{GameState, SearchEngine, SearchEvent, SearchLimits};
+
+#[test]
+fn event_stream() {
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("1r5k/8/8/8/8/8/1P6/KR6 b - - 0 1")));
+    search_engine.set_position(&position, 0);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(2000));
+
+    let (mut sender, receiver) = mpsc::channel();
+    search_engine.search(&limits, &mut sender).unwrap();
+    drop(sender);
+
+    let events = receiver.iter().collect::<Vec<_>>();
+    let mate = Move::from_squares(Square::B8, Square::A8, MoveFlag::QUIET_MOVE);
+
+    assert!(matches!(events.first(), Some(SearchEvent::Started)));
+    assert!(matches!(events.last(), Some(SearchEvent::Ended(result)) if result.iterations() == 2000 && result.best_move() == Some(mate)));
+
+    assert!(events.iter().any(|event| matches!(event, SearchEvent::BestMoveChanged { .. })));
+    assert!(events.iter().any(|event| matches!(event, SearchEvent::Info(result) if result.lines().len() == 1)));
+
+    let proofs = events.iter().filter(|event| matches!(event, SearchEvent::ProofFound { .. })).collect::<Vec<_>>();
+    assert!(matches!(proofs.as_slice(), [SearchEvent::ProofFound { state: GameState::Win(1), .. }]));
+
+    //The second search starts from the reused tree
+    let (mut sender, receiver) = mpsc::channel();
+    search_engine.search(&limits, &mut sender).unwrap();
+    drop(sender);
+
+    assert!(matches!(receiver.iter().next(), Some(SearchEvent::TreeReused { carried_visits }) if carried_visits > 0));
+}
@@ -0,0 +1,130 @@
+use std::{sync::Arc, thread, time::Duration};
+
+use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Square, FEN};
+use engine::{NoReport, SearchEngine, SearchLimits, SearchReport};
+
+#[test]
+fn stop_and_wait() {
+    let search_engine = Arc::new(SearchEngine::new());
+
+    let mut limits = SearchLimits::default();
+    limits.set_infinite(true);
+
+    let handle = search_engine.start_search(limits, NoReport).unwrap();
+
+    thread::sleep(Duration::from_millis(200));
+    assert!(!handle.is_finished());
+
+    let pv = handle.peek();
+    assert_ne!(pv.first_move(), Move::NULL);
+    assert!(pv.score().win_chance() + pv.score().draw_chance() <= 1.0);
+
+    handle.stop();
+    let result = handle.wait();
+
+    assert!(result.iterations() > 0);
+    assert!(result.best_move().is_some());
+    assert!(search_engine.is_search_interrupted());
+}
+
+#[test]
+fn search_limits() {
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("1r5k/8/8/8/8/8/1P6/KR6 b - - 0 1")));
+    search_engine.set_position(&position, 0);
+
+    let search_engine = Arc::new(search_engine);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(2000));
+
+    let result = search_engine.start_search(limits, NoReport).unwrap().wait();
+    assert_eq!(result.iterations(), 2000);
+    assert_eq!(result.best_move(), Some(Move::from_squares(Square::B8, Square::A8, MoveFlag::QUIET_MOVE)));
+
+    //Once the handle is gone the engine can be changed again
+    let mut search_engine = Arc::into_inner(search_engine).unwrap();
+    search_engine.reset_position();
+}
+
+#[test]
+fn drop_stops_search() {
+    let search_engine = Arc::new(SearchEngine::new());
+
+    let mut limits = SearchLimits::default();
+    limits.set_infinite(true);
+
+    let handle = search_engine.start_search(limits, NoReport).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    drop(handle);
+
+    assert!(search_engine.is_search_interrupted());
+    assert_eq!(Arc::strong_count(&search_engine), 1);
+}
+
+#[test]
+fn second_search_fails() {
+    let search_engine = Arc::new(SearchEngine::new());
+
+    let mut limits = SearchLimits::default();
+    limits.set_infinite(true);
+
+    let handle = search_engine.start_search(limits, NoReport).unwrap();
+
+    let err = search_engine.search(&SearchLimits::default(), &mut NoReport).unwrap_err();
+    assert!(err.contains("already running"));
+    assert!(search_engine.start_search(SearchLimits::default(), NoReport).is_err());
+
+    //The failed attempts leave the running search alone
+    assert!(search_engine.is_searching());
+    assert!(!handle.is_finished());
+}
+
+struct PanickingReport;
+impl SearchReport for PanickingReport {
+    fn refresh_rate_per_second(&self) -> f64 {
+        1.0
+    }
+
+    fn search_started(&mut self, _: &SearchLimits, _: &SearchEngine) {
+        panic!("report failed");
+    }
+}
+
+#[test]
+fn panicking_search_ends() {
+    let search_engine = SearchEngine::new();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| search_engine.search(&iteration_limits(500), &mut PanickingReport)));
+    assert!(result.is_err());
+    assert!(!search_engine.is_searching());
+
+    let limits = iteration_limits(500);
+    let result = thread::scope(|s| search_engine.spawn_search(s, &limits, PanickingReport).unwrap().join());
+    assert!(result.is_err());
+    assert!(!search_engine.is_searching());
+
+    assert_eq!(search_engine.search(&iteration_limits(500), &mut NoReport).unwrap().iterations(), 500);
+}
+
+#[test]
+fn search_after_wait() {
+    let search_engine = Arc::new(SearchEngine::new());
+
+    search_engine.start_search(iteration_limits(500), NoReport).unwrap().wait();
+    assert!(!search_engine.is_searching());
+
+    let handle = search_engine.start_search(iteration_limits(500), NoReport).unwrap();
+    assert!(search_engine.is_searching());
+    drop(handle);
+
+    assert!(!search_engine.is_searching());
+    assert_eq!(search_engine.search(&iteration_limits(500), &mut NoReport).unwrap().iterations(), 500);
+}
+
+fn iteration_limits(iters: u64) -> SearchLimits {
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(iters));
+    limits
+}
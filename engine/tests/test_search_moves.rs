@@ -0,0 +1,46 @@
+use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Square, FEN};
+use engine::{NoReport, SearchEngine, SearchLimits};
+
+#[test]
+fn search_moves() { 
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("1r5k/8/8/8/8/8/1P6/KR6 b - - 0 1")));
+
+    search_engine.set_position(&position, 0);
+
+    let allowed = [
+        Move::from_squares(Square::H8, Square::G7, MoveFlag::QUIET_MOVE),
+        Move::from_squares(Square::B8, Square::B7, MoveFlag::QUIET_MOVE),
+    ];
+    
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(2000));
+    limits.set_search_moves(allowed.to_vec());
+
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let tree = search_engine.tree();
+    let best_child = tree.select_best_child(tree.root_index(), 0.5).unwrap();
+    assert!(allowed.contains(&tree[best_child].mv()));
+    assert_eq!(tree.root_moves_count(), 2);
+
+    for pv_idx in 0..4 {
+        assert!(allowed.contains(&tree.get_best_pv(pv_idx, 0.5).first_move()));
+    }
+
+    tree.root_node().map_children(|child_idx| {
+        if !allowed.contains(&tree[child_idx].mv()) {
+            assert_eq!(tree[child_idx].visits(), 0);
+        }
+    });
+
+    //The restriction only lasts for one search
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(2000));
+
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let best_move = search_engine.tree().get_best_pv(0, 0.5).first_move();
+    assert_eq!(best_move, Move::from_squares(Square::B8, Square::A8, MoveFlag::QUIET_MOVE))
+}
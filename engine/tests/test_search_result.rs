@@ -0,0 +1,52 @@
+use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Square, FEN};
+use engine::{LineScore, NoReport, SearchEngine, SearchLimits};
+
+#[test]
+fn mate_line() {
+    let mut search_engine = SearchEngine::new();
+
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("1r5k/8/8/8/8/8/1P6/KR6 b - - 0 1")));
+    search_engine.set_position(&position, 0);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(2000));
+
+    let result = search_engine.search(&limits, &mut NoReport).unwrap();
+    let mate = Move::from_squares(Square::B8, Square::A8, MoveFlag::QUIET_MOVE);
+
+    assert_eq!(result.best_move(), Some(mate));
+    assert_eq!(result.iterations(), 2000);
+    assert!(result.nodes() >= result.iterations());
+    assert!(result.max_depth() >= result.avg_depth());
+
+    assert_eq!(result.lines().len(), 1);
+    assert_eq!(result.lines()[0].pv().first_move(), mate);
+    assert_eq!(result.lines()[0].score(), LineScore::Mate(1));
+    assert_eq!(result.lines()[0].score().to_string(), "mate 1");
+}
+
+#[test]
+fn multi_pv_lines() {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("MultiPV", "3").unwrap();
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(5000));
+
+    let result = search_engine.search(&limits, &mut NoReport).unwrap();
+    let lines = result.lines();
+
+    assert_eq!(lines.len(), 3);
+    assert!(result.best_move().is_some());
+    assert!(result.ponder_move().is_some());
+
+    for line in lines {
+        let wdl = line.wdl();
+        assert!(matches!(line.score(), LineScore::Cp(_)));
+        assert!(wdl.win_chance() >= 0.0 && wdl.draw_chance() >= 0.0 && wdl.win_chance() + wdl.draw_chance() <= 1.0 + 1e-9);
+    }
+
+    //Lines are ordered and start with different moves
+    assert!(lines.windows(2).all(|pair| pair[0].pv().score().single() >= pair[1].pv().score().single()));
+    assert_ne!(lines[0].pv().first_move(), lines[1].pv().first_move());
+}
@@ -0,0 +1,133 @@
+use engine::simd::{self, scalar};
+use utils::Random;
+
+//Kernels under test, the compile time selection and every x86-64 set the running CPU supports
+struct Kernels {
+    name: &'static str,
+    add_i16: fn(&mut [i16], &[i16]),
+    sub_i16: fn(&mut [i16], &[i16]),
+    add_f32: fn(&mut [f32], &[f32]),
+    sub_f32: fn(&mut [f32], &[f32]),
+    madd_f32: fn(&mut [f32], f32, &[f32]),
+    screlu_dot: fn(&[i16], &[i16], i16) -> i32,
+    relu_dot: fn(&[f32], &[f32]) -> f32,
+}
+
+macro_rules! kernels {
+    ($name:literal, $module:path) => {{
+        use $module as kernels;
+
+        //Only built after the CPU features were detected
+        Kernels {
+            name: $name,
+            add_i16: |acc, weights| unsafe { kernels::add_i16(acc, weights) },
+            sub_i16: |acc, weights| unsafe { kernels::sub_i16(acc, weights) },
+            add_f32: |acc, weights| unsafe { kernels::add_f32(acc, weights) },
+            sub_f32: |acc, weights| unsafe { kernels::sub_f32(acc, weights) },
+            madd_f32: |acc, mul, weights| unsafe { kernels::madd_f32(acc, mul, weights) },
+            screlu_dot: |inputs, weights, max| unsafe { kernels::screlu_dot(inputs, weights, max) },
+            relu_dot: |a, b| unsafe { kernels::relu_dot(a, b) },
+        }
+    }};
+}
+
+#[allow(unused_unsafe)]
+fn kernel_sets() -> Vec<Kernels> {
+    let mut sets = vec![kernels!("selected", simd)];
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("sse4.1") {
+            sets.push(kernels!("sse", simd::sse));
+        }
+
+        if is_x86_feature_detected!("avx2") {
+            sets.push(kernels!("avx2", simd::avx2));
+        }
+
+        if is_x86_feature_detected!("avx512bw") {
+            sets.push(kernels!("avx512", simd::avx512));
+        }
+    }
+
+    sets
+}
+
+fn random_i16(random: &mut Random, len: usize) -> Vec<i16> {
+    (0..len).map(|_| random.next_u64() as i16).collect()
+}
+
+fn random_f32(random: &mut Random, len: usize) -> Vec<f32> {
+    (0..len).map(|_| random.next_f32() * 4.0 - 2.0).collect()
+}
+
+#[test]
+fn i16_add_sub() {
+    for kernels in kernel_sets() {
+        let mut random = Random::new(0);
+
+        for _ in 0..64 {
+            let acc = random_i16(&mut random, 3072);
+            let weights = random_i16(&mut random, 3072);
+
+            let (mut expected, mut result) = (acc.clone(), acc.clone());
+            scalar::add_i16(&mut expected, &weights);
+            (kernels.add_i16)(&mut result, &weights);
+            assert_eq!(result, expected, "{}", kernels.name);
+
+            let (mut expected, mut result) = (acc.clone(), acc);
+            scalar::sub_i16(&mut expected, &weights);
+            (kernels.sub_i16)(&mut result, &weights);
+            assert_eq!(result, expected, "{}", kernels.name);
+        }
+    }
+}
+
+#[test]
+fn screlu_dot() {
+    for kernels in kernel_sets() {
+        let mut random = Random::new(0);
+
+        for _ in 0..64 {
+            let inputs = random_i16(&mut random, 3072);
+            let weights = random_i16(&mut random, 3072);
+
+            assert_eq!((kernels.screlu_dot)(&inputs, &weights, 255), scalar::screlu_dot(&inputs, &weights, 255), "{}", kernels.name);
+        }
+
+        let inputs = [i16::MAX; 64];
+        let weights = [i16::MIN; 64];
+        assert_eq!((kernels.screlu_dot)(&inputs, &weights, 255), scalar::screlu_dot(&inputs, &weights, 255), "{}", kernels.name);
+    }
+}
+
+#[test]
+fn f32_kernels() {
+    for kernels in kernel_sets() {
+        let mut random = Random::new(0);
+
+        for _ in 0..64 {
+            let acc = random_f32(&mut random, 32);
+            let weights = random_f32(&mut random, 32);
+            let mul = random.next_f32();
+
+            let (mut expected, mut result) = (acc.clone(), acc.clone());
+            scalar::add_f32(&mut expected, &weights);
+            (kernels.add_f32)(&mut result, &weights);
+            assert_eq!(result, expected, "{}", kernels.name);
+
+            let (mut expected, mut result) = (acc.clone(), acc.clone());
+            scalar::sub_f32(&mut expected, &weights);
+            (kernels.sub_f32)(&mut result, &weights);
+            assert_eq!(result, expected, "{}", kernels.name);
+
+            let (mut expected, mut result) = (acc.clone(), acc.clone());
+            scalar::madd_f32(&mut expected, mul, &weights);
+            (kernels.madd_f32)(&mut result, mul, &weights);
+            assert_eq!(result, expected, "{}", kernels.name);
+
+            assert_eq!((kernels.relu_dot)(&acc, &weights).to_bits(), scalar::relu_dot(&acc, &weights).to_bits(), "{}", kernels.name);
+        }
+    }
+}
+
@@ -0,0 +1,234 @@
+use std::sync::Mutex;
+
+use chess::{ChessBoard, ChessPosition, Move, MoveFlag, Piece, Side, Square, FEN};
+use engine::{load_syzygy, probe_dtz, probe_root, probe_wdl, syzygy_max_pieces, LineScore, NoReport, SearchEngine, SearchLimits, TablebaseWDL};
+
+//Fixture tables for KQvK, KRvK, KNvK, KBvK, KPvK and KQvKR, KBvK covers underpromotions. They are
+//generated by tbgen with the index encoding of the probing code, not copies of the official files.
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/syzygy");
+
+//Loaded tables are global, tests that change them must not run at the same time
+static TABLEBASES: Mutex<()> = Mutex::new(());
+
+fn board(fen: &str) -> ChessBoard {
+    ChessBoard::from(&FEN::from(fen))
+}
+
+#[test]
+fn syzygy_path() {
+    let _lock = TABLEBASES.lock().unwrap();
+    let empty_dir = std::env::temp_dir().join("jackal_test_syzygy_empty");
+    std::fs::create_dir_all(&empty_dir).unwrap();
+
+    assert!(load_syzygy("").is_ok());
+    assert_eq!(syzygy_max_pieces(), 0);
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/8/3QK3 w - - 0 1")), None);
+
+    assert!(load_syzygy("/this/path/does/not/exist").is_err());
+    assert!(load_syzygy(empty_dir.to_str().unwrap()).is_err());
+    assert_eq!(syzygy_max_pieces(), 0);
+
+    let mut search_engine = SearchEngine::new();
+    assert!(search_engine.set_option("SyzygyPath", "/this/path/does/not/exist").is_err());
+    assert!(search_engine.options().syzygy_path().is_empty());
+}
+
+#[test]
+fn syzygy_fixtures() {
+    let _lock = TABLEBASES.lock().unwrap();
+    load_syzygy(FIXTURES).unwrap();
+    assert!(syzygy_max_pieces() >= 4);
+
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/8/3QK3 w - - 0 1")), Some(TablebaseWDL::Win));
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/8/3QK3 b - - 0 1")), Some(TablebaseWDL::Loss));
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/8/3NK3 w - - 0 1")), Some(TablebaseWDL::Draw));
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/8/4K2r w - - 0 1")), Some(TablebaseWDL::Loss));
+
+    //Black to move can capture the hanging rook
+    assert_eq!(probe_wdl(&board("4k3/3R4/8/8/8/8/8/4K3 b - - 0 1")), Some(TablebaseWDL::Draw));
+
+    let dtz = probe_dtz(&board("4k3/8/8/8/8/8/8/3RK3 w - - 0 1")).unwrap();
+    assert!(dtz > 0 && dtz < 100);
+    let dtz = probe_dtz(&board("4k3/8/8/8/8/8/8/3RK3 b - - 0 1")).unwrap();
+    assert!(dtz < 0 && dtz > -100);
+    assert_eq!(probe_dtz(&board("4k3/8/8/8/8/8/8/3NK3 w - - 0 1")), Some(0));
+
+    //Pawn endings, outside the square of the pawn and a rook pawn with the king in the corner
+    assert_eq!(probe_wdl(&board("8/8/8/8/8/8/k3P3/7K w - - 0 1")), Some(TablebaseWDL::Win));
+    assert_eq!(probe_wdl(&board("8/k3p3/8/8/8/8/8/7K b - - 0 1")), Some(TablebaseWDL::Win));
+    assert_eq!(probe_wdl(&board("k7/8/8/8/8/8/P7/K7 w - - 0 1")), Some(TablebaseWDL::Draw));
+    assert_eq!(probe_dtz(&board("8/8/8/8/8/8/k3P3/7K w - - 0 1")), Some(1));
+
+    //Queen against rook, winning the hanging rook or trading it off
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/3r4/3QK3 w - - 0 1")), Some(TablebaseWDL::Win));
+    assert_eq!(probe_dtz(&board("4k3/8/8/8/8/8/3r4/3QK3 w - - 0 1")), Some(1));
+    assert_eq!(probe_wdl(&board("3rk3/8/8/8/8/8/8/3QK3 b - - 0 1")), Some(TablebaseWDL::Draw));
+    assert_eq!(probe_wdl(&board("3rk3/8/8/8/8/8/8/3QK3 w - - 0 1")), Some(TablebaseWDL::Win));
+
+    //Only moves that keep the queen win
+    let position = ChessPosition::from(board("4k3/8/8/8/8/8/8/3QK3 w - - 0 1"));
+    let moves = probe_root(&position).unwrap();
+    assert!(!moves.is_empty());
+    assert!(!moves.contains(&Move::from_squares(Square::D1, Square::D7, MoveFlag::QUIET_MOVE)));
+
+    //Castling rights are never probed
+    assert_eq!(probe_wdl(&board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1")), None);
+
+    load_syzygy("").unwrap();
+}
+
+#[test]
+fn syzygy_longest_wins() {
+    let _lock = TABLEBASES.lock().unwrap();
+    load_syzygy(FIXTURES).unwrap();
+
+    //Longest mates are known to be 10 moves with a queen and 16 with a rook, and winning the
+    //rook with a queen takes at most 31 moves. DTZ counts the plies of the winning side up to it.
+    assert_eq!(probe_dtz(&board("7K/6Q1/8/8/8/3k4/8/8 w - - 0 1")), Some(19));
+    assert_eq!(probe_dtz(&board("7K/8/8/4R3/3k4/8/8/8 w - - 0 1")), Some(31));
+    assert_eq!(probe_dtz(&board("8/8/8/8/7Q/2kr3K/8/8 w - - 0 1")), Some(61));
+
+    load_syzygy("").unwrap();
+}
+
+#[test]
+fn syzygy_wins_rank_below_mates() {
+    let _lock = TABLEBASES.lock().unwrap();
+    load_syzygy(FIXTURES).unwrap();
+
+    //Only taking the knight reaches a won table, every other move stays outside of the tables
+    let fen = "k6r/3n4/8/8/3Q4/8/8/6K1 w - - 0 1";
+    let capture = Move::from_squares(Square::D4, Square::D7, MoveFlag::CAPTURE);
+
+    let mut after_capture = board(fen);
+    after_capture.make_move(capture, &after_capture.castle_rights().get_castle_mask());
+    assert_eq!(probe_wdl(&after_capture), Some(TablebaseWDL::Loss));
+
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_position(&ChessPosition::from(board(fen)), 0);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(3000));
+    let result = search_engine.search(&limits, &mut NoReport).unwrap();
+
+    assert_eq!(result.best_move(), Some(capture));
+    assert_eq!(result.lines()[0].score(), LineScore::Cp(20000));
+
+    //A mate still beats the tablebase win, Qxh4 wins by tables and Qd8 mates at once
+    let fen = "k7/8/1K6/8/3Q3n/8/8/4r3 w - - 0 1";
+    let capture = Move::from_squares(Square::D4, Square::H4, MoveFlag::CAPTURE);
+
+    let mut after_capture = board(fen);
+    after_capture.make_move(capture, &after_capture.castle_rights().get_castle_mask());
+    assert_eq!(probe_wdl(&after_capture), Some(TablebaseWDL::Loss));
+
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("MultiPV", "2").unwrap();
+    search_engine.set_position(&ChessPosition::from(board(fen)), 0);
+    let result = search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let mate = Move::from_squares(Square::D4, Square::D8, MoveFlag::QUIET_MOVE);
+    assert_eq!(result.best_move(), Some(mate));
+
+    //Lines are ordered by score, where the mate and the tablebase win can tie
+    let scores = result.lines().iter().map(|line| (line.pv().first_move(), line.score())).collect::<Vec<_>>();
+    assert!(scores.contains(&(mate, LineScore::Mate(1))), "{scores:?}");
+    assert!(scores.contains(&(capture, LineScore::Cp(20000))), "{scores:?}");
+
+    load_syzygy("").unwrap();
+}
+
+//Random positions have to agree with a 1-ply search over the probed values of their children
+#[test]
+fn syzygy_fixture_consistency() {
+    let _lock = TABLEBASES.lock().unwrap();
+    load_syzygy(FIXTURES).unwrap();
+
+    let empty = ["k7/8/8/8/8/8/8/7K w - - 0 1", "k7/8/8/8/8/8/8/7K b - - 0 1"].map(|fen| {
+        let mut board = board(fen);
+        board.remove_piece_on_square(Square::H1, Piece::KING, Side::WHITE);
+        board.remove_piece_on_square(Square::A8, Piece::KING, Side::BLACK);
+        board
+    });
+
+    let material: [&[(Piece, Side)]; 5] = [
+        &[(Piece::QUEEN, Side::WHITE)],
+        &[(Piece::ROOK, Side::WHITE)],
+        &[(Piece::PAWN, Side::WHITE)],
+        &[(Piece::PAWN, Side::BLACK)],
+        &[(Piece::QUEEN, Side::WHITE), (Piece::ROOK, Side::BLACK)],
+    ];
+
+    let mut seed = 0x2545_F491_4F6C_DD1Du64;
+    let mut random_square = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        Square::from_value((seed % 64) as u8)
+    };
+
+    for pieces in material {
+        let mut checked = 0;
+        while checked < 40 {
+            let mut board = empty[checked % 2];
+            let mut occupied = Vec::new();
+
+            for (piece, side) in [(Piece::KING, Side::WHITE), (Piece::KING, Side::BLACK)].iter().chain(pieces) {
+                let square = random_square();
+                if occupied.contains(&square) || (*piece == Piece::PAWN && (square.get_rank() == 0 || square.get_rank() == 7)) {
+                    break;
+                }
+
+                occupied.push(square);
+                board.set_piece_on_square(square, *piece, *side);
+            }
+
+            let other = board.side().flipped();
+            if occupied.len() != pieces.len() + 2 || board.is_square_attacked(board.king_square(other), other) {
+                continue;
+            }
+
+            let mask = board.castle_rights().get_castle_mask();
+            let mut moves = Vec::new();
+            board.map_legal_moves(|mv| moves.push(mv));
+
+            let mut best = if moves.is_empty() && !board.is_in_check() { TablebaseWDL::Draw } else { TablebaseWDL::Loss };
+            let mut shortest_win = i32::MAX;
+            let mut longest_loss = 1;
+
+            for mv in moves {
+                let zeroing = mv.is_capture() || board.piece_on_square(mv.get_from_square()) == Piece::PAWN;
+
+                let mut child = board;
+                child.make_move(mv, &mask);
+
+                let mut child_moves = 0;
+                child.map_legal_moves(|_| child_moves += 1);
+                let mate = child_moves == 0 && child.is_in_check();
+
+                let value = probe_wdl(&child).unwrap().reversed();
+                let plies = if zeroing || mate { 1 } else { probe_dtz(&child).unwrap().abs() + 1 };
+
+                best = best.max(value);
+                longest_loss = longest_loss.max(plies);
+                if value == TablebaseWDL::Win {
+                    shortest_win = shortest_win.min(plies);
+                }
+            }
+
+            let fen = FEN::from(&board);
+            assert_eq!(probe_wdl(&board), Some(best), "{fen}");
+
+            let dtz = match best {
+                TablebaseWDL::Win => shortest_win,
+                TablebaseWDL::Loss => -longest_loss,
+                _ => 0,
+            };
+            assert_eq!(probe_dtz(&board), Some(dtz), "{fen}");
+
+            checked += 1;
+        }
+    }
+
+    load_syzygy("").unwrap();
+}
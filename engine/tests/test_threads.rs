@@ -0,0 +1,34 @@
+use engine::{NoReport, SearchEngine, SearchLimits};
+
+#[test]
+fn resize_threads() {
+    let mut search_engine = SearchEngine::new();
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(5000));
+
+    //Searches reuse the same workers, including across tree resets and option changes
+    for threads in ["4", "4", "2", "1", "3"] {
+        let _ = search_engine.set_option("Threads", threads);
+        search_engine.tree().clear();
+
+        let result = search_engine.search(&limits, &mut NoReport).unwrap();
+        assert!(result.iterations() >= 5000);
+        assert!(result.best_move().is_some());
+    }
+}
+
+#[test]
+fn small_tree_swaps() {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("Threads", "3").unwrap();
+    search_engine.set_option("Hash", "1").unwrap();
+    search_engine.resize_tree();
+
+    //A tiny tree forces many half swaps, each of them restarts the workers
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(100_000));
+
+    let result = search_engine.search(&limits, &mut NoReport).unwrap();
+    assert!(result.iterations() >= 100_000);
+}
@@ -0,0 +1,131 @@
+use chess::{ChessBoard, ChessPosition, Move, FEN};
+use engine::{NoReport, NodeIndex, SearchEngine, SearchLimits, Tree};
+
+fn find_move(position: &ChessPosition, text: &str) -> Move {
+    let mut result = Move::NULL;
+    position.board().map_legal_moves(|mv| {
+        if mv.to_string(false) == text {
+            result = mv;
+        }
+    });
+
+    result
+}
+
+fn find_node(tree: &Tree, root: &ChessPosition, line: &[&str]) -> Option<NodeIndex> {
+    let mut position = *root;
+    let mut node_idx = tree.root_index();
+    for text in line {
+        let mv = find_move(&position, text);
+        position.make_move_no_mask(mv);
+
+        let mut next = None;
+        tree[node_idx].map_children(|child_idx| {
+            if tree[child_idx].mv() == mv {
+                next = Some(child_idx);
+            }
+        });
+
+        node_idx = next?;
+    }
+
+    Some(node_idx)
+}
+
+//Children index of both nodes and the visits gathered below the second one
+fn search_both_orders(transpositions: bool, first: [&str; 3], second: [&str; 3]) -> (NodeIndex, NodeIndex, i64) {
+    let mut search_engine = SearchEngine::new();
+    if transpositions {
+        search_engine.set_option("Transpositions", "true").unwrap();
+    }
+
+    let root = ChessPosition::from(ChessBoard::from(&FEN::start_position()));
+    search_engine.set_position(&root, 0);
+
+    //The first line is searched first, then the same position is reached through the second one
+    for (first_move, iters) in [(first[0], 10000), (second[0], 2000)] {
+        let mut limits = SearchLimits::default();
+        limits.set_iters(Some(iters));
+        limits.set_search_moves(vec![find_move(&root, first_move)]);
+        search_engine.search(&limits, &mut NoReport).unwrap();
+    }
+
+    let tree = search_engine.tree();
+    let searched = find_node(tree, &root, &first).unwrap();
+    let transposed = find_node(tree, &root, &second).unwrap();
+
+    assert!(tree[searched].children_count() > 0);
+    assert!(tree[transposed].children_count() > 0);
+
+    let mut children_visits = 0;
+    tree[transposed].map_children(|child_idx| children_visits += i64::from(tree[child_idx].visits()));
+
+    let searched_children = *tree[searched].children_index();
+    let transposed_children = *tree[transposed].children_index();
+
+    (searched_children, transposed_children, children_visits - i64::from(tree[transposed].visits()))
+}
+
+//1. e3 e5 2. d3 and 1. d3 e5 2. e3, the last moves are pawn moves so the history starts over
+const PAWN_ORDER: [[&str; 3]; 2] = [["e2e3", "e7e5", "d2d3"], ["d2d3", "e7e5", "e2e3"]];
+
+//1. Nf3 Nf6 2. Nc3 and 1. Nc3 Nf6 2. Nf3, no zeroing move on the way
+const KNIGHT_ORDER: [[&str; 3]; 2] = [["g1f3", "g8f6", "b1c3"], ["b1c3", "g8f6", "g1f3"]];
+
+#[test]
+fn shared_children() {
+    //Without the option each order expands the position on its own
+    let (searched, transposed, extra_visits) = search_both_orders(false, PAWN_ORDER[0], PAWN_ORDER[1]);
+    assert_ne!(searched, transposed);
+    assert!(extra_visits < 0);
+
+    //With it, both nodes point at one block that holds the visits of both orders
+    let (searched, transposed, extra_visits) = search_both_orders(true, PAWN_ORDER[0], PAWN_ORDER[1]);
+    assert_eq!(searched, transposed);
+    assert!(extra_visits > 0);
+}
+
+#[test]
+fn quiet_transpositions_are_not_shared() {
+    //Only positions after a zeroing move share children, so the knight orders stay apart even with the option
+    let (searched, transposed, extra_visits) = search_both_orders(true, KNIGHT_ORDER[0], KNIGHT_ORDER[1]);
+    assert_ne!(searched, transposed);
+    assert!(extra_visits < 0);
+}
+
+fn assert_legal_children(tree: &Tree, node_idx: NodeIndex, position: &ChessPosition, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+
+    let mut legal_moves = Vec::new();
+    position.board().map_legal_moves(|mv| legal_moves.push(mv));
+
+    tree[node_idx].map_children(|child_idx| {
+        let mv = tree[child_idx].mv();
+        assert!(legal_moves.contains(&mv), "{} is not legal", mv.to_string(false));
+
+        let mut child_position = *position;
+        child_position.make_move_no_mask(mv);
+        assert_legal_children(tree, child_idx, &child_position, depth - 1);
+    });
+}
+
+#[test]
+fn shared_children_across_half_swaps() {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_option("Transpositions", "true").unwrap();
+    search_engine.set_option("Hash", "1").unwrap();
+    search_engine.set_option("Threads", "4").unwrap();
+
+    let root = ChessPosition::from(ChessBoard::from(&FEN::start_position()));
+    search_engine.set_position(&root, 0);
+
+    //The tree fills up many times, so blocks are copied between halves and joined again by other parents
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(200000));
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let tree = search_engine.tree();
+    assert_legal_children(tree, tree.root_index(), &root, 6);
+}
@@ -0,0 +1,67 @@
+use chess::{ChessBoard, ChessPosition, FEN};
+use engine::{NoReport, SearchEngine, SearchLimits};
+
+#[test]
+fn save_and_load() {
+    let path = std::env::temp_dir().join("jackal_test_tree.bin");
+    let path = path.to_str().unwrap();
+
+    let mut search_engine = SearchEngine::new();
+    let position = ChessPosition::from(ChessBoard::from(&FEN::from("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")));
+    search_engine.set_position(&position, 4);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(3000));
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    search_engine.save_tree(path).unwrap();
+
+    let mut loaded_engine = SearchEngine::new();
+    loaded_engine.load_tree(path).unwrap();
+
+    assert_eq!(loaded_engine.root_position().board().hash(), position.board().hash());
+
+    let (tree, loaded_tree) = (search_engine.tree(), loaded_engine.tree());
+    assert_eq!(loaded_tree.root_node().visits(), tree.root_node().visits());
+    assert_eq!(loaded_tree.root_node().children_count(), tree.root_node().children_count());
+
+    for idx in 0..tree.root_node().children_count() {
+        let child = &tree[*tree.root_node().children_index() + idx];
+        let loaded_child = &loaded_tree[*loaded_tree.root_node().children_index() + idx];
+
+        assert_eq!(loaded_child.mv(), child.mv());
+        assert_eq!(loaded_child.visits(), child.visits());
+        assert_eq!(loaded_child.score(), child.score());
+        assert_eq!(loaded_child.policy(), child.policy());
+        assert_eq!(loaded_child.state(), child.state());
+        assert_eq!(loaded_child.children_count(), child.children_count());
+    }
+
+    assert_eq!(loaded_tree.get_best_pv(0, 0.5).to_string(false), tree.get_best_pv(0, 0.5).to_string(false));
+
+    //Searching the loaded tree continues from the saved visits
+    loaded_engine.search(&limits, &mut NoReport).unwrap();
+    assert!(loaded_tree.root_node().visits() > tree.root_node().visits());
+}
+
+#[test]
+fn invalid_files() {
+    let path = std::env::temp_dir().join("jackal_test_invalid_tree.bin");
+    let path = path.to_str().unwrap();
+
+    let mut search_engine = SearchEngine::new();
+    assert!(search_engine.load_tree("/this/path/does/not/exist").is_err());
+
+    std::fs::write(path, b"not a tree file").unwrap();
+    assert!(search_engine.load_tree(path).is_err());
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(500));
+    search_engine.search(&limits, &mut NoReport).unwrap();
+    search_engine.save_tree(path).unwrap();
+
+    let bytes = std::fs::read(path).unwrap();
+    std::fs::write(path, &bytes[..bytes.len() - 10]).unwrap();
+    assert!(search_engine.load_tree(path).is_err());
+    assert_eq!(search_engine.tree().root_node().visits(), 0);
+}
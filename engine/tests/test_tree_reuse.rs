@@ -0,0 +1,178 @@
+use chess::{ChessBoard, ChessPosition, Move, FEN};
+use engine::{GameState, NoReport, NodeIndex, SearchEngine, SearchLimits, SearchReport, Tree};
+
+#[derive(Default)]
+struct ReuseReport {
+    carried_visits: u32,
+}
+
+impl SearchReport for ReuseReport {
+    fn refresh_rate_per_second(&self) -> f64 {
+        1.0
+    }
+
+    fn tree_reused(&mut self, _: &SearchLimits, carried_visits: u32, _: &SearchEngine) {
+        self.carried_visits = carried_visits;
+    }
+}
+
+fn find_move(position: &ChessPosition, text: &str) -> Move {
+    let mut result = Move::NULL;
+    position.board().map_legal_moves(|mv| {
+        if mv.to_string(false) == text {
+            result = mv;
+        }
+    });
+
+    result
+}
+
+fn count_draws(tree: &Tree, node_idx: NodeIndex) -> usize {
+    let mut draws = usize::from(tree[node_idx].state() == GameState::Draw);
+    tree[node_idx].map_children(|child_idx| draws += count_draws(tree, child_idx));
+    draws
+}
+
+fn searched_engine(fen: &str) -> SearchEngine {
+    let mut search_engine = SearchEngine::new();
+    search_engine.set_position(&ChessPosition::from(ChessBoard::from(&FEN::from(fen))), 0);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(20000));
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    search_engine
+}
+
+#[test]
+fn reuse_deep_position() {
+    let mut search_engine = searched_engine("8/8/4k3/8/8/4K3/4P3/8 w - - 0 1");
+    let tree = search_engine.tree();
+
+    //Follow the most visited expanded line for four plies
+    let mut target = *search_engine.root_position();
+    let mut node_idx = tree.root_index();
+    for _ in 0..4 {
+        let mut best = None;
+        tree[node_idx].map_children(|child_idx| {
+            if tree[child_idx].children_count() > 0 && best.is_none_or(|best_idx| tree[child_idx].visits() > tree[best_idx].visits()) {
+                best = Some(child_idx);
+            }
+        });
+
+        node_idx = best.unwrap();
+        target.make_move_no_mask(tree[node_idx].mv());
+    }
+
+    let expected_visits = tree[node_idx].visits();
+    let expected_children = tree[node_idx].children_count();
+    assert!(expected_visits > 0);
+
+    assert!(tree.try_reuse(search_engine.root_position(), &target, 4, search_engine.options()).is_some());
+    assert_eq!(tree.root_node().visits(), expected_visits);
+    assert_eq!(tree.root_node().children_count(), expected_children);
+
+    search_engine.set_position(&target, 4);
+
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(100));
+    let mut report = ReuseReport::default();
+    search_engine.search(&limits, &mut report).unwrap();
+    assert_eq!(report.carried_visits, expected_visits);
+}
+
+#[test]
+fn reuse_transposition() {
+    let mut search_engine = SearchEngine::new();
+    let root = ChessPosition::from(ChessBoard::from(&FEN::start_position()));
+    search_engine.set_position(&root, 0);
+
+    //Only 1. Nf3 is searched, so 1. Nc3 Nf6 2. Nf3 can only be found as a transposition of 1. Nf3 Nf6 2. Nc3
+    let mut limits = SearchLimits::default();
+    limits.set_iters(Some(20000));
+    limits.set_search_moves(vec![find_move(&root, "g1f3")]);
+    search_engine.search(&limits, &mut NoReport).unwrap();
+
+    let tree = search_engine.tree();
+
+    let mut position = root;
+    let mut node_idx = tree.root_index();
+    for mv in ["g1f3", "g8f6", "b1c3"] {
+        let mv = find_move(&position, mv);
+        position.make_move_no_mask(mv);
+
+        let mut next = None;
+        tree[node_idx].map_children(|child_idx| {
+            if tree[child_idx].mv() == mv {
+                next = Some(child_idx);
+            }
+        });
+
+        node_idx = next.unwrap();
+    }
+
+    let expected_visits = tree[node_idx].visits();
+    assert!(tree[node_idx].children_count() > 0);
+
+    let mut target = root;
+    for mv in ["b1c3", "g8f6", "g1f3"] {
+        target.make_move_no_mask(find_move(&target, mv));
+    }
+
+    assert_eq!(target.board().hash(), position.board().hash());
+    assert!(tree.try_reuse(search_engine.root_position(), &target, 3, search_engine.options()).is_some());
+    assert_eq!(tree.root_node().visits(), expected_visits);
+}
+
+#[test]
+fn reuse_missing_position() {
+    let search_engine = searched_engine("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    let tree = search_engine.tree();
+
+    let target = ChessPosition::from(ChessBoard::from(&FEN::from("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1")));
+    assert!(tree.try_reuse(search_engine.root_position(), &target, 2, search_engine.options()).is_none());
+    assert_eq!(tree.root_node().visits(), 0);
+    assert_eq!(tree.root_node().children_count(), 0);
+}
+
+#[test]
+fn reuse_resets_draws() {
+    let search_engine = searched_engine("8/8/4k3/8/8/4K3/4P3/8 w - - 0 1");
+    let tree = search_engine.tree();
+
+    //Two plies down the most visited line, where king moves already repeated positions in the search
+    let mut target = *search_engine.root_position();
+    let mut node_idx = tree.root_index();
+    for _ in 0..2 {
+        let mut best = tree.root_index();
+        tree[node_idx].map_children(|child_idx| {
+            if tree[child_idx].visits() > tree[best].visits() || best == tree.root_index() {
+                best = child_idx;
+            }
+        });
+
+        node_idx = best;
+        target.make_move_no_mask(tree[node_idx].mv());
+    }
+
+    assert!(count_draws(tree, node_idx) > 0);
+
+    //Those repetitions counted positions before the new root, so they are found again from its history
+    assert!(tree.try_reuse(search_engine.root_position(), &target, 2, search_engine.options()).is_some());
+    assert_eq!(count_draws(tree, tree.root_index()), 0);
+}
+
+#[test]
+fn reuse_only_at_played_depth() {
+    let search_engine = searched_engine("8/8/4k3/8/8/4K3/4P3/8 w - - 0 1");
+    let tree = search_engine.tree();
+
+    let mut target = *search_engine.root_position();
+    for mv in ["e3d3", "e6d6"] {
+        target.make_move_no_mask(find_move(&target, mv));
+    }
+
+    //The position after Kd3 Kd6 is in the tree, but only two plies deep
+    assert!(tree.clone().try_reuse(search_engine.root_position(), &target, 2, search_engine.options()).is_some());
+    assert!(tree.try_reuse(search_engine.root_position(), &target, 3, search_engine.options()).is_none());
+}
@@ -0,0 +1,144 @@
+use std::sync::LazyLock;
+
+use chess::{Attacks, Square};
+
+//Index tables shared by every table, squares are plain 0..63 values (a1 = 0, h8 = 63).
+pub struct Encoding {
+    //Squares below the a1-h8 diagonal to 0..27
+    pub map_b1h1h7: [u64; 64],
+    //Squares of the a1-d1-d4 triangle to 0..9, the diagonal comes last
+    pub map_a1d1d4: [u64; 64],
+    //All 462 legal king pairs with the first king in the a1-d1-d4 triangle
+    pub map_kk: [[u64; 64]; 10],
+    pub binomial: [[u64; 64]; 7],
+    //Pawn squares a2-h7 to 0..47, the leading pawn is the one with the highest value
+    pub map_pawns: [u64; 64],
+    pub lead_pawn_idx: [[u64; 64]; 6],
+    pub lead_pawns_size: [[u64; 4]; 6],
+}
+
+pub static ENCODING: LazyLock<Encoding> = LazyLock::new(Encoding::new);
+
+impl Encoding {
+    fn new() -> Self {
+        let mut result = Self {
+            map_b1h1h7: [0; 64],
+            map_a1d1d4: [0; 64],
+            map_kk: [[0; 64]; 10],
+            binomial: [[0; 64]; 7],
+            map_pawns: [0; 64],
+            lead_pawn_idx: [[0; 64]; 6],
+            lead_pawns_size: [[0; 4]; 6],
+        };
+
+        let mut code = 0;
+        for square in 0..64 {
+            if off_a1h8(square) < 0 {
+                result.map_b1h1h7[square as usize] = code;
+                code += 1;
+            }
+        }
+
+        let mut diagonal = Vec::new();
+        let mut code = 0;
+        for square in 0..=27u8 {
+            if off_a1h8(square) < 0 && file(square) <= 3 {
+                result.map_a1d1d4[square as usize] = code;
+                code += 1;
+            } else if off_a1h8(square) == 0 && file(square) <= 3 {
+                diagonal.push(square);
+            }
+        }
+
+        for square in diagonal {
+            result.map_a1d1d4[square as usize] = code;
+            code += 1;
+        }
+
+        let mut both_on_diagonal = Vec::new();
+        let mut code = 0;
+        for idx in 0..10 {
+            for first in 0..=27u8 {
+                //b1 is mapped to 0, a1 is only used when it is the diagonal entry
+                if result.map_a1d1d4[first as usize] != idx as u64 || (idx == 0 && first != 1) {
+                    continue;
+                }
+
+                let blocked = Attacks::get_king_attacks(Square::from_value(first)).get_value() | (1u64 << first);
+                for second in 0..64u8 {
+                    if blocked & (1u64 << second) != 0 || (off_a1h8(first) == 0 && off_a1h8(second) > 0) {
+                        continue;
+                    }
+
+                    if off_a1h8(first) == 0 && off_a1h8(second) == 0 {
+                        both_on_diagonal.push((idx, second));
+                    } else {
+                        result.map_kk[idx][second as usize] = code;
+                        code += 1;
+                    }
+                }
+            }
+        }
+
+        for (idx, square) in both_on_diagonal {
+            result.map_kk[idx][square as usize] = code;
+            code += 1;
+        }
+
+        result.binomial[0][0] = 1;
+        for n in 1..64 {
+            for k in 0..7.min(n + 1) {
+                result.binomial[k][n] = if k > 0 { result.binomial[k - 1][n - 1] } else { 0 } + if k < n { result.binomial[k][n - 1] } else { 0 };
+            }
+        }
+
+        let mut available_squares = 48;
+        for lead_pawns_count in 1..6 {
+            for file in 0..4u8 {
+                let mut idx = 0;
+
+                for rank in 1..7u8 {
+                    let square = rank * 8 + file;
+
+                    if lead_pawns_count == 1 {
+                        available_squares -= 2;
+                        result.map_pawns[square as usize] = available_squares + 1;
+                        result.map_pawns[flip_file(square) as usize] = available_squares;
+                    }
+
+                    result.lead_pawn_idx[lead_pawns_count][square as usize] = idx;
+                    idx += result.binomial[lead_pawns_count - 1][result.map_pawns[square as usize] as usize];
+                }
+
+                result.lead_pawns_size[lead_pawns_count][file as usize] = idx;
+            }
+        }
+
+        result
+    }
+}
+
+#[inline]
+pub fn rank(square: u8) -> u8 {
+    square >> 3
+}
+
+#[inline]
+pub fn file(square: u8) -> u8 {
+    square & 7
+}
+
+#[inline]
+pub fn flip_file(square: u8) -> u8 {
+    square ^ 7
+}
+
+#[inline]
+pub fn flip_rank(square: u8) -> u8 {
+    square ^ 56
+}
+
+#[inline]
+pub fn off_a1h8(square: u8) -> i8 {
+    rank(square) as i8 - file(square) as i8
+}
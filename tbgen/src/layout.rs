@@ -0,0 +1,232 @@
+use chess::{Bitboard, ChessBoard, Piece, Side};
+
+use crate::encoding::{file, flip_file, flip_rank, off_a1h8, rank, ENCODING};
+use crate::material::Material;
+
+#[derive(Debug, Default, Clone)]
+pub struct Pairs {
+    pub pieces: [u8; 7],
+    pub group_len: [usize; 8],
+    pub group_idx: [u64; 8],
+}
+
+impl Pairs {
+    pub fn size(&self) -> u64 {
+        let groups = self.group_len.iter().position(|&len| len == 0).unwrap();
+        self.group_idx[groups]
+    }
+}
+
+//Table layout with both sides to move, pairs index is side * 4 + file like WDL tables in the decoder
+pub struct Layout {
+    pub name: String,
+    pub material: Material,
+    pub max_file: usize,
+    pub order: Vec<u8>,
+    pub pairs: Vec<Pairs>,
+}
+
+impl Layout {
+    pub fn new(name: &str, order: &[u8]) -> Self {
+        let material = Material::from_name(name).unwrap();
+        let max_file = if material.has_pawns { 4 } else { 1 };
+        let mut layout = Self { name: name.to_string(), material, max_file, order: order.to_vec(), pairs: vec![Pairs::default(); 8] };
+
+        for side in 0..2 {
+            for file in 0..max_file {
+                layout.pairs[side * 4 + file].pieces[..order.len()].copy_from_slice(order);
+                layout.set_groups(side * 4 + file, [0, 15], file);
+            }
+        }
+
+        layout
+    }
+
+    fn set_groups(&mut self, pairs_idx: usize, order: [u8; 2], file: usize) {
+        let material = &self.material;
+        let pairs = &mut self.pairs[pairs_idx];
+
+        let mut n = 0;
+        let mut first_len: i32 = if material.has_pawns {
+            0
+        } else if material.has_unique_pieces {
+            3
+        } else {
+            2
+        };
+
+        pairs.group_len[n] = 1;
+        for idx in 1..material.piece_count {
+            first_len -= 1;
+            if first_len > 0 || pairs.pieces[idx] == pairs.pieces[idx - 1] {
+                pairs.group_len[n] += 1;
+            } else {
+                n += 1;
+                pairs.group_len[n] = 1;
+            }
+        }
+
+        n += 1;
+        pairs.group_len[n] = 0;
+
+        let both_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut next = if both_pawns { 2 } else { 1 };
+        let mut free_squares = 64 - pairs.group_len[0] - if both_pawns { pairs.group_len[1] } else { 0 };
+        let mut idx = 1u64;
+
+        let mut k = 0;
+        while next < n || k == usize::from(order[0]) || k == usize::from(order[1]) {
+            if k == usize::from(order[0]) {
+                pairs.group_idx[0] = idx;
+                idx *= if material.has_pawns {
+                    ENCODING.lead_pawns_size[pairs.group_len[0]][file]
+                } else if material.has_unique_pieces {
+                    31332
+                } else {
+                    462
+                };
+            } else if k == usize::from(order[1]) {
+                pairs.group_idx[1] = idx;
+                idx *= ENCODING.binomial[pairs.group_len[1]][48 - pairs.group_len[0]];
+            } else {
+                pairs.group_idx[next] = idx;
+                idx *= ENCODING.binomial[pairs.group_len[next]][free_squares];
+                free_squares -= pairs.group_len[next];
+                next += 1;
+            }
+
+            k += 1;
+        }
+
+        pairs.group_idx[n] = idx;
+    }
+
+    //(side to move in table orientation, file, index) exactly like Table::probe
+    pub fn encode(&self, board: &ChessBoard, material_key: u64) -> (usize, usize, u64) {
+        let encoding = &*ENCODING;
+        let material = &self.material;
+
+        let symmetric_black_to_move = material.key == material.key2 && board.side() == Side::BLACK;
+        let black_stronger = material_key != material.key;
+        let flip = symmetric_black_to_move || black_stronger;
+
+        let flip_color = if flip { 8 } else { 0 };
+        let flip_squares = if flip { 56 } else { 0 };
+        let stm = usize::from(flip) ^ usize::from(board.side().get_value());
+
+        let mut squares = [0u8; 7];
+        let mut pieces = [0u8; 7];
+        let mut size = 0;
+        let mut lead_pawns = Bitboard::EMPTY;
+        let mut table_file = 0;
+
+        if material.has_pawns {
+            let lead_color = (self.pairs[0].pieces[0] ^ flip_color) >> 3;
+            lead_pawns = board.piece_mask_for_side(Piece::PAWN, Side::from(lead_color));
+
+            lead_pawns.map(|square| {
+                squares[size] = square.get_value() ^ flip_squares;
+                size += 1;
+            });
+
+            let lead_idx = (0..size).max_by_key(|&idx| encoding.map_pawns[usize::from(squares[idx])]).unwrap();
+            squares.swap(0, lead_idx);
+
+            table_file = usize::from(file(squares[0]).min(7 - file(squares[0])));
+        }
+
+        let lead_pawns_count = size;
+
+        (board.occupancy() & !lead_pawns).map(|square| {
+            let piece = u8::from(board.piece_on_square(square)) + 1 + 8 * board.color_on_square(square).get_value();
+            squares[size] = square.get_value() ^ flip_squares;
+            pieces[size] = piece ^ flip_color;
+            size += 1;
+        });
+
+        let pairs = &self.pairs[stm * 4 + table_file];
+
+        for idx in lead_pawns_count..size.saturating_sub(1) {
+            if let Some(other) = (idx + 1..size).find(|&other| pairs.pieces[idx] == pieces[other]) {
+                pieces.swap(idx, other);
+                squares.swap(idx, other);
+            }
+        }
+
+        assert_eq!(pieces[lead_pawns_count..size], pairs.pieces[lead_pawns_count..size]);
+
+        if file(squares[0]) > 3 {
+            squares[..size].iter_mut().for_each(|square| *square = flip_file(*square));
+        }
+
+        let mut idx = if material.has_pawns {
+            let mut idx = encoding.lead_pawn_idx[lead_pawns_count][usize::from(squares[0])];
+
+            squares[1..lead_pawns_count].sort_by_key(|&square| encoding.map_pawns[usize::from(square)]);
+            for (pawn, &square) in squares[1..lead_pawns_count].iter().enumerate() {
+                idx += encoding.binomial[pawn + 1][encoding.map_pawns[usize::from(square)] as usize];
+            }
+
+            idx
+        } else {
+            if rank(squares[0]) > 3 {
+                squares[..size].iter_mut().for_each(|square| *square = flip_rank(*square));
+            }
+
+            if let Some(first) = (0..pairs.group_len[0]).find(|&idx| off_a1h8(squares[idx]) != 0) {
+                if off_a1h8(squares[first]) > 0 {
+                    squares[first..size].iter_mut().for_each(|square| *square = ((*square >> 3) | (*square << 3)) & 63);
+                }
+            }
+
+            if material.has_unique_pieces {
+                encode_unique_pieces(&squares)
+            } else {
+                encoding.map_kk[encoding.map_a1d1d4[usize::from(squares[0])] as usize][usize::from(squares[1])]
+            }
+        };
+
+        idx *= pairs.group_idx[0];
+
+        let mut remaining_pawns = material.has_pawns && material.pawn_count[1] > 0;
+        let mut group_start = pairs.group_len[0];
+        let mut next = 1;
+
+        while pairs.group_len[next] != 0 {
+            let group_len = pairs.group_len[next];
+            squares[group_start..group_start + group_len].sort_unstable();
+
+            let mut n = 0;
+            for idx in 0..group_len {
+                let square = squares[group_start + idx];
+                let adjust = squares[..group_start].iter().filter(|&&other| square > other).count();
+                n += encoding.binomial[idx + 1][usize::from(square) - adjust - 8 * usize::from(remaining_pawns)];
+            }
+
+            remaining_pawns = false;
+            idx += n * pairs.group_idx[next];
+            group_start += group_len;
+            next += 1;
+        }
+
+        (stm, table_file, idx)
+    }
+}
+
+fn encode_unique_pieces(squares: &[u8; 7]) -> u64 {
+    let encoding = &*ENCODING;
+    let [first, second, third] = [squares[0], squares[1], squares[2]].map(u64::from);
+
+    let adjust1 = u64::from(second > first);
+    let adjust2 = u64::from(third > first) + u64::from(third > second);
+
+    if off_a1h8(squares[0]) != 0 {
+        (encoding.map_a1d1d4[first as usize] * 63 + (second - adjust1)) * 62 + third - adjust2
+    } else if off_a1h8(squares[1]) != 0 {
+        (6 * 63 + u64::from(rank(squares[0])) * 28 + encoding.map_b1h1h7[second as usize]) * 62 + third - adjust2
+    } else if off_a1h8(squares[2]) != 0 {
+        6 * 63 * 62 + 4 * 28 * 62 + u64::from(rank(squares[0])) * 7 * 28 + (u64::from(rank(squares[1])) - adjust1) * 28 + encoding.map_b1h1h7[third as usize]
+    } else {
+        6 * 63 * 62 + 4 * 28 * 62 + 4 * 7 * 28 + u64::from(rank(squares[0])) * 7 * 6 + (u64::from(rank(squares[1])) - adjust1) * 6 + (u64::from(rank(squares[2])) - adjust2)
+    }
+}
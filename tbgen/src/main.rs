@@ -0,0 +1,44 @@
+use crate::solver::Solver;
+
+mod encoding;
+mod layout;
+mod material;
+mod solver;
+mod writer;
+
+//-----------------------------------------------
+// Generates the small Syzygy tables used by the engine tests:
+//
+//   cargo run -r -p tbgen -- engine/tests/syzygy
+//
+// Every table is solved from the rules of chess with the values of the tables it converts
+// into, then checked against a 1-ply search of every placement (every 7th one for 4 pieces)
+// before it is written. Tables are written with the same index encoding as the probing code
+// in engine/src/syzygy, so they don't stand in for a check against the official files.
+//-----------------------------------------------
+
+//Name and piece order, smaller tables come first because bigger ones convert into them
+const TABLES: [(&str, &[u8]); 6] = [
+    ("KNvK", &[2, 6, 14]),
+    ("KBvK", &[3, 6, 14]),
+    ("KRvK", &[4, 6, 14]),
+    ("KQvK", &[5, 6, 14]),
+    ("KPvK", &[1, 6, 14]),
+    ("KQvKR", &[5, 6, 14, 12]),
+];
+
+fn main() {
+    let Some(output) = std::env::args().nth(1) else {
+        println!("Usage: tbgen <output directory>");
+        return;
+    };
+
+    let mut solver = Solver::new();
+    for (name, order) in TABLES {
+        let solved = solver.solve(name, order);
+        if let Err(err) = writer::write_tables(solved, &output) {
+            println!("Failed to write {name} to '{output}': {err}");
+            return;
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+
+use chess::{ChessBoard, Piece, Side, Square, FEN};
+
+use crate::{layout::Layout, material::material_key};
+
+const UNKNOWN: i8 = i8::MAX;
+pub const DONT_CARE: i8 = i8::MIN;
+const DTZ_UNKNOWN: i16 = i16::MAX;
+
+pub struct Solved {
+    pub layout: Layout,
+    //Per pairs index (stm * 4 + file), wdl from the side to move, DONT_CARE for unused indices
+    pub wdl: Vec<Vec<i8>>,
+    //Per pairs index, signed dtz in plies, 0 for draws and unused indices
+    pub dtz: Vec<Vec<i16>>,
+}
+
+//Retrograde solver for tables without castling rights or en passant. Positions are
+//enumerated piece by piece, mapped to their table index, and solved by index with the
+//values of smaller tables for captures and promotions.
+pub struct Solver {
+    solved: Vec<Solved>,
+    keys: HashMap<u64, usize>,
+    bases: [ChessBoard; 2],
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        //Empty boards with either side to move, pieces are set on them square by square
+        let bases = ["k7/8/8/8/8/8/8/7K w - - 0 1", "k7/8/8/8/8/8/8/7K b - - 0 1"].map(|fen| {
+            let mut board = ChessBoard::from(&FEN::from(fen));
+            board.remove_piece_on_square(Square::H1, Piece::KING, Side::WHITE);
+            board.remove_piece_on_square(Square::A8, Piece::KING, Side::BLACK);
+            board
+        });
+
+        Self { solved: Vec::new(), keys: HashMap::new(), bases }
+    }
+
+    fn lookup_wdl(&self, board: &ChessBoard) -> i8 {
+        if board.occupancy().pop_count() == 2 {
+            return 0;
+        }
+
+        let key = material_key(board);
+        let solved = &self.solved[*self.keys.get(&key).unwrap_or_else(|| panic!("no table for {}", FEN::from(board)))];
+        let (stm, file, idx) = solved.layout.encode(board, key);
+        let value = solved.wdl[stm * 4 + file][idx as usize];
+        assert!(value != DONT_CARE && value != UNKNOWN, "bad lookup {}", FEN::from(board));
+        value
+    }
+
+    //Pieces of the order use the file format codes, P = 1 to K = 6 and 8 added for black
+    pub fn solve(&mut self, name: &str, order: &[u8]) -> &Solved {
+        let layout = Layout::new(name, order);
+        let key = layout.material.key;
+        let pieces = order.iter().map(|&piece| (Piece::from((piece & 7) - 1), Side::from(piece >> 3))).collect::<Vec<_>>();
+
+        let mut base = [0usize; 9];
+        for side in 0..2 {
+            for file in 0..4 {
+                let pairs_idx = side * 4 + file;
+                let size = if file < layout.max_file { layout.pairs[pairs_idx].size() as usize } else { 0 };
+                base[pairs_idx + 1] = base[pairs_idx] + size;
+            }
+        }
+        let states = base[8];
+        println!("{name}: {states} states");
+
+        //Representative placement of every index, squares in order plus the side to move
+        let mut reps = vec![u32::MAX; states];
+        let mut placements = 0u64;
+        self.map_placements(&pieces, |board, code| {
+            let (stm, file, idx) = layout.encode(board, key);
+            let id = base[stm * 4 + file] + idx as usize;
+            if reps[id] == u32::MAX {
+                reps[id] = code;
+            }
+            placements += 1;
+        });
+        println!("  {placements} placements, {} indices used", reps.iter().filter(|&&rep| rep != u32::MAX).count());
+
+        //Children of every state: internal ids, or external wdl values, with a zeroing bit
+        const ZEROING: u32 = 1 << 31;
+        const EXTERNAL: u32 = 1 << 30;
+
+        let mut offsets = vec![0u32; states + 1];
+        let mut children = Vec::new();
+        let mut mated = vec![false; states];
+
+        for id in 0..states {
+            if reps[id] != u32::MAX {
+                let board = self.board_from_code(&pieces, reps[id]);
+                let mask = board.castle_rights().get_castle_mask();
+                let mut moves = Vec::new();
+                board.map_legal_moves(|mv| moves.push(mv));
+
+                mated[id] = moves.is_empty() && board.is_in_check();
+
+                for mv in moves {
+                    let zeroing = mv.is_capture() || board.piece_on_square(mv.get_from_square()) == Piece::PAWN;
+                    let mut child = board;
+                    child.make_move(mv, &mask);
+
+                    let item = if material_key(&child) == key {
+                        let (stm, file, idx) = layout.encode(&child, key);
+                        (base[stm * 4 + file] + idx as usize) as u32
+                    } else {
+                        assert!(zeroing);
+                        EXTERNAL | (self.lookup_wdl(&child) + 2) as u32
+                    };
+
+                    children.push(item | if zeroing { ZEROING } else { 0 });
+                }
+            }
+
+            offsets[id + 1] = children.len() as u32;
+        }
+
+        let child_wdl = |wdl: &[i8], item: u32| -> i8 {
+            if item & EXTERNAL != 0 {
+                (item & 0xFF) as i8 - 2
+            } else {
+                wdl[(item & !ZEROING) as usize]
+            }
+        };
+
+        let mut wdl = vec![UNKNOWN; states];
+        for id in 0..states {
+            if reps[id] == u32::MAX {
+                wdl[id] = DONT_CARE;
+            } else if offsets[id] == offsets[id + 1] {
+                wdl[id] = if mated[id] { -2 } else { 0 };
+            }
+        }
+
+        let mut passes = 0;
+        loop {
+            let mut changed = false;
+            for id in 0..states {
+                if wdl[id] != UNKNOWN {
+                    continue;
+                }
+
+                let items = &children[offsets[id] as usize..offsets[id + 1] as usize];
+                if items.iter().any(|&item| child_wdl(&wdl, item) == -2) {
+                    wdl[id] = 2;
+                    changed = true;
+                } else if items.iter().all(|&item| child_wdl(&wdl, item) == 2) {
+                    wdl[id] = -2;
+                    changed = true;
+                }
+            }
+
+            passes += 1;
+            if !changed {
+                break;
+            }
+        }
+
+        wdl.iter_mut().filter(|value| **value == UNKNOWN).for_each(|value| *value = 0);
+        println!("  wdl solved in {passes} passes: {} wins, {} draws, {} losses",
+            wdl.iter().filter(|&&v| v == 2).count(), wdl.iter().filter(|&&v| v == 0).count(), wdl.iter().filter(|&&v| v == -2).count());
+
+        //Plies to the next zeroing move, with the same conventions as the probing code
+        let mut dtz = vec![DTZ_UNKNOWN; states];
+        for id in 0..states {
+            if wdl[id] == 0 || wdl[id] == DONT_CARE {
+                dtz[id] = 0;
+            }
+        }
+
+        let mut level = 1i16;
+        loop {
+            let mut updates = Vec::new();
+
+            for id in 0..states {
+                if dtz[id] != DTZ_UNKNOWN {
+                    continue;
+                }
+
+                let items = &children[offsets[id] as usize..offsets[id + 1] as usize];
+
+                if wdl[id] == 2 {
+                    let found = items.iter().any(|&item| {
+                        let zeroing = item & ZEROING != 0;
+                        let value = child_wdl(&wdl, item);
+                        if value != -2 {
+                            return false;
+                        }
+
+                        if zeroing {
+                            return level == 1;
+                        }
+
+                        let child = (item & !ZEROING) as usize;
+                        if mated[child] {
+                            return level == 1;
+                        }
+
+                        dtz[child] == -(level - 1)
+                    });
+
+                    if found {
+                        updates.push((id, level));
+                    }
+                } else {
+                    if mated[id] {
+                        updates.push((id, -1));
+                        continue;
+                    }
+
+                    let mut longest = 0;
+                    let mut resolved = true;
+                    for &item in items {
+                        if item & ZEROING != 0 {
+                            longest = longest.max(1);
+                            continue;
+                        }
+
+                        let child = (item & !ZEROING) as usize;
+                        if dtz[child] == DTZ_UNKNOWN {
+                            resolved = false;
+                            break;
+                        }
+
+                        assert!(dtz[child] > 0);
+                        longest = longest.max(dtz[child] + 1);
+                    }
+
+                    if resolved {
+                        assert_eq!(longest, level, "loss resolved off level");
+                        updates.push((id, -longest));
+                    }
+                }
+            }
+
+            let done = updates.is_empty() && dtz.iter().all(|&value| value != DTZ_UNKNOWN);
+            for (id, value) in updates {
+                dtz[id] = value;
+            }
+
+            if done {
+                break;
+            }
+
+            level += 1;
+            assert!(level < 100, "dtz does not converge");
+        }
+
+        println!("  dtz solved, longest {} plies", dtz.iter().map(|value| value.abs()).max().unwrap());
+
+        if let Some(id) = (base[0]..base[4]).filter(|&id| wdl[id] == 2).max_by_key(|&id| dtz[id]) {
+            println!("  longest win {} plies: {}", dtz[id], FEN::from(&self.board_from_code(&pieces, reps[id])));
+        }
+
+        let mut solved = Solved { layout, wdl: vec![Vec::new(); 8], dtz: vec![Vec::new(); 8] };
+        for pairs_idx in 0..8 {
+            solved.wdl[pairs_idx] = wdl[base[pairs_idx]..base[pairs_idx + 1]].to_vec();
+            solved.dtz[pairs_idx] = dtz[base[pairs_idx]..base[pairs_idx + 1]].to_vec();
+        }
+
+        let idx = self.solved.len();
+        self.keys.insert(solved.layout.material.key, idx);
+        self.keys.insert(solved.layout.material.key2, idx);
+        self.solved.push(solved);
+
+        self.verify(idx, &pieces);
+        &self.solved[idx]
+    }
+
+    //Every placement has to agree with a 1-ply search over its own moves, which catches indices shared by different positions
+    fn verify(&self, idx: usize, pieces: &[(Piece, Side)]) {
+        let solved = &self.solved[idx];
+        let key = solved.layout.material.key;
+        let mut checked = 0u64;
+
+        let state = |board: &ChessBoard| {
+            let (stm, file, idx) = solved.layout.encode(board, key);
+            (solved.wdl[stm * 4 + file][idx as usize], solved.dtz[stm * 4 + file][idx as usize])
+        };
+
+        let step = if pieces.len() > 3 { 7 } else { 1 };
+        let mut counter = 0u64;
+        self.map_placements(pieces, |board, _| {
+            counter += 1;
+            if !counter.is_multiple_of(step) {
+                return;
+            }
+
+            let (wdl, dtz) = state(board);
+            let mask = board.castle_rights().get_castle_mask();
+            let mut moves = Vec::new();
+            board.map_legal_moves(|mv| moves.push(mv));
+
+            let mut best = -2;
+            let mut best_win = i16::MAX;
+            let mut longest_loss = 0;
+            for &mv in &moves {
+                let zeroing = mv.is_capture() || board.piece_on_square(mv.get_from_square()) == Piece::PAWN;
+                let mut child = *board;
+                child.make_move(mv, &mask);
+
+                let (child_wdl, child_dtz) = if material_key(&child) == key { state(&child) } else { (self.lookup_wdl(&child), 0) };
+                best = best.max(-child_wdl);
+
+                let mut child_moves = 0;
+                child.map_legal_moves(|_| child_moves += 1);
+                let mate = child_moves == 0 && child.is_in_check();
+
+                let length = if zeroing || mate { 1 } else { child_dtz.abs() + 1 };
+                if child_wdl == -2 {
+                    best_win = best_win.min(length);
+                }
+                longest_loss = longest_loss.max(length);
+            }
+
+            let expected_wdl = if moves.is_empty() { if board.is_in_check() { -2 } else { 0 } } else { best };
+            assert_eq!(wdl, expected_wdl, "wdl mismatch {}", FEN::from(board));
+
+            let expected_dtz = match expected_wdl {
+                2 => best_win,
+                -2 if moves.is_empty() => -1,
+                -2 => -longest_loss,
+                _ => 0,
+            };
+            assert_eq!(dtz, expected_dtz, "dtz mismatch {}", FEN::from(board));
+
+            checked += 1;
+        });
+
+        println!("  verified {checked} placements");
+    }
+
+    fn board_from_code(&self, pieces: &[(Piece, Side)], code: u32) -> ChessBoard {
+        let mut board = self.bases[(code >> 31) as usize];
+        for (idx, &(piece, side)) in pieces.iter().enumerate() {
+            board.set_piece_on_square(Square::from_value(((code >> (idx * 6)) & 63) as u8), piece, side);
+        }
+        board
+    }
+
+    fn map_placements<F: FnMut(&ChessBoard, u32)>(&self, pieces: &[(Piece, Side)], mut method: F) {
+        let count = pieces.len();
+        let mut squares = vec![0u8; count];
+
+        loop {
+            let distinct = (0..count).all(|a| (a + 1..count).all(|b| squares[a] != squares[b]));
+            let pawns_ok = pieces.iter().zip(&squares).all(|(&(piece, _), &square)| piece != Piece::PAWN || (8..56).contains(&square));
+
+            if distinct && pawns_ok {
+                for stm in 0..2u32 {
+                    let code = squares.iter().enumerate().fold(stm << 31, |code, (idx, &square)| code | u32::from(square) << (idx * 6));
+                    let board = self.board_from_code(pieces, code);
+
+                    let other = board.side().flipped();
+                    if board.is_square_attacked(board.king_square(other), other) {
+                        continue;
+                    }
+
+                    method(&board, code);
+                }
+            }
+
+            let mut idx = 0;
+            loop {
+                if idx == count {
+                    return;
+                }
+
+                squares[idx] += 1;
+                if squares[idx] < 64 {
+                    break;
+                }
+
+                squares[idx] = 0;
+                idx += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+
+use crate::solver::{Solved, DONT_CARE};
+
+const WDL_MAGIC: [u8; 4] = [0x71, 0xE8, 0x23, 0x5D];
+const DTZ_MAGIC: [u8; 4] = [0xD7, 0x66, 0x0C, 0xA5];
+
+const WIN_PLIES_FLAG: u8 = 4;
+const LOSS_PLIES_FLAG: u8 = 8;
+const SINGLE_VALUE_FLAG: u8 = 128;
+
+const BLOCK_LOG: u8 = 10;
+const SPAN_LOG: u8 = 10;
+const MAX_CODE_LEN: u8 = 24;
+
+struct Compressed {
+    flags: u8,
+    single: Option<u8>,
+    min_len: u8,
+    max_len: u8,
+    lowest_sym: Vec<u16>,
+    symbols: Vec<u8>,
+    sparse: Vec<(u32, u16)>,
+    block_lengths: Vec<u16>,
+    blocks: Vec<Vec<u8>>,
+}
+
+//Writes the .rtbw and .rtbz files, WDL for both sides to move and DTZ for the stronger side only
+pub fn write_tables(solved: &Solved, out: &str) -> std::io::Result<()> {
+    let layout = &solved.layout;
+    let max_file = layout.max_file;
+
+    let mut wdl = Vec::new();
+    for file in 0..max_file {
+        for side in 0..2 {
+            let values = solved.wdl[side * 4 + file].iter().map(|&value| (value != DONT_CARE).then(|| (value + 2) as u16)).collect::<Vec<_>>();
+            wdl.push(compress(&values, 0));
+        }
+    }
+
+    let mut dtz = Vec::new();
+    for file in 0..max_file {
+        let values = solved.wdl[file].iter().zip(&solved.dtz[file]).map(|(&wdl, &dtz)| {
+            (wdl == 2 || wdl == -2).then(|| (dtz.abs() - 1) as u16)
+        }).collect::<Vec<_>>();
+        dtz.push(compress(&values, WIN_PLIES_FLAG | LOSS_PLIES_FLAG));
+    }
+
+    let name = &layout.name;
+    std::fs::write(format!("{out}/{name}.rtbw"), serialize(solved, &wdl, 2, WDL_MAGIC))?;
+    std::fs::write(format!("{out}/{name}.rtbz"), serialize(solved, &dtz, 1, DTZ_MAGIC))
+}
+
+fn serialize(solved: &Solved, tables: &[Compressed], sides: usize, magic: [u8; 4]) -> Vec<u8> {
+    let layout = &solved.layout;
+    let max_file = layout.max_file;
+    let mut bytes = magic.to_vec();
+    bytes.push(1 | if layout.material.has_pawns { 2 } else { 0 });
+
+    for _ in 0..max_file {
+        //Group order 0 for both sides, no second pawn group in these tables
+        bytes.push(0);
+        bytes.extend(layout.order.iter().map(|&piece| piece | if sides == 2 { piece << 4 } else { 0 }));
+    }
+
+    align(&mut bytes, 2);
+
+    for table in tables {
+        bytes.push(table.flags);
+        if let Some(value) = table.single {
+            bytes.push(value);
+            continue;
+        }
+
+        bytes.extend([BLOCK_LOG, SPAN_LOG, 0]);
+        bytes.extend((table.blocks.len() as u32).to_le_bytes());
+        bytes.extend([table.max_len, table.min_len]);
+        for &lowest in &table.lowest_sym {
+            bytes.extend(lowest.to_le_bytes());
+        }
+
+        bytes.extend((table.symbols.len() as u16 / 3).to_le_bytes());
+        bytes.extend(&table.symbols);
+        if (table.symbols.len() / 3) & 1 != 0 {
+            bytes.push(0);
+        }
+    }
+
+    //No DTZ maps, values are stored directly
+    align(&mut bytes, 2);
+
+    for table in tables {
+        for &(block, offset) in &table.sparse {
+            bytes.extend(block.to_le_bytes());
+            bytes.extend(offset.to_le_bytes());
+        }
+    }
+
+    for table in tables {
+        for &length in &table.block_lengths {
+            bytes.extend(length.to_le_bytes());
+        }
+    }
+
+    for table in tables {
+        align(&mut bytes, 64);
+        for block in &table.blocks {
+            bytes.extend(block);
+        }
+    }
+
+    bytes.extend([0; 64]);
+    bytes
+}
+
+fn align(bytes: &mut Vec<u8>, to: usize) {
+    while !bytes.len().is_multiple_of(to) {
+        bytes.push(0);
+    }
+}
+
+fn compress(values: &[Option<u16>], flags: u8) -> Compressed {
+    let mut counts = std::collections::BTreeMap::<u16, u64>::new();
+    values.iter().flatten().for_each(|&value| *counts.entry(value).or_default() += 1);
+
+    let fill = counts.iter().max_by_key(|(_, &count)| count).map(|(&value, _)| value).unwrap_or(0);
+    let values = values.iter().map(|value| value.unwrap_or(fill)).collect::<Vec<_>>();
+
+    let mut result = Compressed {
+        flags,
+        single: None,
+        min_len: 0,
+        max_len: 0,
+        lowest_sym: Vec::new(),
+        symbols: Vec::new(),
+        sparse: Vec::new(),
+        block_lengths: Vec::new(),
+        blocks: Vec::new(),
+    };
+
+    if counts.len() <= 1 {
+        result.flags |= SINGLE_VALUE_FLAG;
+        result.single = Some(fill as u8);
+        return result;
+    }
+
+    let symbols = counts.keys().copied().collect::<Vec<_>>();
+    let mut weights = counts.values().copied().collect::<Vec<_>>();
+    let lengths = loop {
+        let lengths = code_lengths(&weights);
+        if lengths.iter().all(|&len| len <= MAX_CODE_LEN) {
+            break lengths;
+        }
+
+        weights.iter_mut().for_each(|weight| *weight = *weight / 2 + 1);
+    };
+
+    let min_len = *lengths.iter().min().unwrap();
+    let max_len = *lengths.iter().max().unwrap();
+
+    //Longest codes get the lowest symbol indices
+    let mut order = (0..symbols.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| Reverse(lengths[idx]));
+
+    let count_of = |len: u8| lengths.iter().filter(|&&other| other == len).count() as u64;
+
+    let mut lowest = vec![0u64; usize::from(max_len) + 1];
+    let mut base = vec![0u64; usize::from(max_len) + 1];
+    for len in (min_len..max_len).rev() {
+        let len = usize::from(len);
+        lowest[len] = lowest[len + 1] + count_of(len as u8 + 1);
+        base[len] = (base[len + 1] + count_of(len as u8 + 1)) / 2;
+    }
+    assert_eq!(base[usize::from(min_len)] + count_of(min_len), 1 << min_len, "incomplete code");
+
+    let mut codes = vec![(0u64, 0u8); symbols.len()];
+    let mut next = lowest.clone();
+    let mut sym_index = std::collections::HashMap::new();
+    let mut table = vec![0u16; symbols.len()];
+    for &idx in &order {
+        let len = usize::from(lengths[idx]);
+        let sym = next[len];
+        next[len] += 1;
+        codes[idx] = (base[len] + (sym - lowest[len]), lengths[idx]);
+        table[sym as usize] = symbols[idx];
+        sym_index.insert(symbols[idx], idx);
+    }
+
+    for &value in &table {
+        let left = value as u32;
+        let right = 0xFFFu32;
+        result.symbols.extend([(left & 0xFF) as u8, ((left >> 8) & 0xF | (right & 0xF) << 4) as u8, (right >> 4) as u8]);
+    }
+
+    result.min_len = min_len;
+    result.max_len = max_len;
+    result.lowest_sym = (min_len..=max_len).map(|len| lowest[usize::from(len)] as u16).collect();
+
+    //Pack values into blocks, each starting on its own boundary
+    let block_bits = (1u64 << BLOCK_LOG) * 8;
+    let mut starts = Vec::new();
+    let mut writer = BitWriter::default();
+    let mut in_block = 0u64;
+    for (pos, &value) in values.iter().enumerate() {
+        let (code, len) = codes[sym_index[&value]];
+        if writer.bits + u64::from(len) > block_bits || in_block == 65536 || pos == 0 {
+            if pos != 0 {
+                result.block_lengths.push((in_block - 1) as u16);
+                result.blocks.push(writer.finish(1 << BLOCK_LOG));
+            }
+
+            writer = BitWriter::default();
+            starts.push(pos as u64);
+            in_block = 0;
+        }
+
+        writer.push(code, len);
+        in_block += 1;
+    }
+
+    result.block_lengths.push((in_block - 1) as u16);
+    result.blocks.push(writer.finish(1 << BLOCK_LOG));
+
+    let size = values.len() as u64;
+    let span = 1u64 << SPAN_LOG;
+    for k in 0..size.div_ceil(span) {
+        let position = k * span + span / 2;
+        let block = starts.partition_point(|&start| start <= position.min(size - 1)) - 1;
+        let offset = position - starts[block];
+        result.sparse.push((block as u32, u16::try_from(offset).unwrap()));
+    }
+
+    result
+}
+
+fn code_lengths(weights: &[u64]) -> Vec<u8> {
+    let mut heap = BinaryHeap::new();
+    let mut parents = vec![usize::MAX; weights.len()];
+    for (idx, &weight) in weights.iter().enumerate() {
+        heap.push(Reverse((weight, idx)));
+    }
+
+    while heap.len() > 1 {
+        let Reverse((first, a)) = heap.pop().unwrap();
+        let Reverse((second, b)) = heap.pop().unwrap();
+        let node = parents.len();
+        parents.push(usize::MAX);
+        parents[a] = node;
+        parents[b] = node;
+        heap.push(Reverse((first + second, node)));
+    }
+
+    (0..weights.len()).map(|mut idx| {
+        let mut len = 0;
+        while parents[idx] != usize::MAX {
+            idx = parents[idx];
+            len += 1;
+        }
+        len
+    }).collect()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u64,
+    bits: u64,
+}
+
+impl BitWriter {
+    fn push(&mut self, code: u64, len: u8) {
+        for bit in (0..len).rev() {
+            self.current = (self.current << 1) | ((code >> bit) & 1);
+            self.bits += 1;
+            if self.bits.is_multiple_of(8) {
+                self.bytes.push(self.current as u8);
+                self.current = 0;
+            }
+        }
+    }
+
+    fn finish(mut self, size: usize) -> Vec<u8> {
+        let pending = self.bits % 8;
+        if pending != 0 {
+            self.bytes.push((self.current << (8 - pending)) as u8);
+        }
+
+        self.bytes.resize(size, 0);
+        self.bytes
+    }
+}
@@ -1,46 +1,46 @@
 use std::io::{self, Write};
 
-use engine::{PvLine, SearchEngine, SearchLimits, SearchReport, SearchStats, Tree, WDLScore};
+use engine::{LineScore, PvLine, SearchEngine, SearchLimits, SearchReport, SearchResult, SearchStats, Tree};
 use utils::{bytes_to_string, clear_terminal_screen, create_loading_bar, heat_color, number_to_string, time_to_string, AlignString, Theme, DRAW_COLOR, LOSE_COLOR, WIN_COLOR};
 
-static mut SEARCH_HISTORY: Vec<(u128, PvLine)> = Vec::new();
+#[derive(Default)]
+pub struct PrettySearchReport {
+    //Time and line of every change of the best move
+    search_history: Vec<(u128, PvLine)>,
+}
 
-pub struct PrettySearchReport;
 impl SearchReport for PrettySearchReport {
-    fn refresh_rate_per_second() -> f64 {
+    fn refresh_rate_per_second(&self) -> f64 {
         20.0
     }
 
-    fn search_started(_: &SearchLimits, _: &SearchEngine) {
+    fn search_started(&mut self, _: &SearchLimits, _: &SearchEngine) {
+        self.search_history.clear();
         clear_terminal_screen();
         
         print!("\x1B[?25l");
         let _ = io::stdout().flush();
     }
 
-    fn search_report(search_limits: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) { 
-        print_search_report::<false>(search_limits, search_stats, search_engine);
+    fn search_report(&mut self, search_limits: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) { 
+        print_search_report::<false>(&mut self.search_history, search_limits, &SearchResult::new(search_stats, search_engine), search_engine);
     }
 
-    fn search_ended(search_limits: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) {
+    fn search_ended(&mut self, search_limits: &SearchLimits, search_result: &SearchResult, search_engine: &SearchEngine) {
         clear_terminal_screen();
 
-        print_search_report::<true>(search_limits, search_stats, search_engine);
-
-        let draw_score = search_engine.options().draw_score() as f64 / 100.0;
-        let best_node_idx = search_engine.tree().select_best_child(search_engine.tree().root_index(), draw_score);
+        print_search_report::<true>(&mut self.search_history, search_limits, search_result, search_engine);
 
         if let Some((x,y)) = term_cursor::get_pos().ok() {
             let _ = term_cursor::set_pos(x, y - 2);
         }
 
-        print!("{}\r", " ".repeat(50));
-        println!( "\n{}",
-            format!(" Best Move: {}", search_engine
-                .tree()[best_node_idx.unwrap()]
-                .mv()
-                .to_string(search_engine.options().chess960()).secondary(1.0)).primary(1.0)
-        );
+        if let Some(best_move) = search_result.best_move() {
+            print!("{}\r", " ".repeat(50));
+            println!( "\n{}",
+                format!(" Best Move: {}", best_move.to_string(search_engine.options().chess960()).secondary(1.0)).primary(1.0)
+            );
+        }
 
         print!("\x1B[?25h");
         let _ = io::stdout().flush();
@@ -49,7 +49,7 @@ impl SearchReport for PrettySearchReport {
 
 const PV_WRAPPING: usize = 13;
 
-fn print_search_report<const FINAL: bool>(_: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) {
+fn print_search_report<const FINAL: bool>(search_history: &mut Vec<(u128, PvLine)>, _: &SearchLimits, result: &SearchResult, search_engine: &SearchEngine) {
     let grad = |a: u8| -> f32 {
         a as f32 / 33.0
     };
@@ -99,9 +99,9 @@ fn print_search_report<const FINAL: bool>(_: &SearchLimits, search_stats: &Searc
     }
 
     print!("{}\r", " ".repeat(t_width));
-    println!("{}", format!(" Avg. Depth: {}", search_stats.avg_depth().to_string().secondary(grad(15))).primary(grad(15)));
+    println!("{}", format!(" Avg. Depth: {}", result.avg_depth().to_string().secondary(grad(15))).primary(grad(15)));
     print!("{}\r", " ".repeat(t_width));
-    println!("{}", format!(" Max Depth:  {}", search_stats.max_depth().to_string().secondary(grad(16))).primary(grad(16)));
+    println!("{}", format!(" Max Depth:  {}", result.max_depth().to_string().secondary(grad(16))).primary(grad(16)));
 
     print!("{}\r", " ".repeat(t_width));
     println!();
@@ -109,22 +109,12 @@ fn print_search_report<const FINAL: bool>(_: &SearchLimits, search_stats: &Searc
     height_used += 3;
 
     if t_height >= 21 {
-        let nodes = if search_engine.options().report_iters() {
-            search_stats.iterations()
-        } else {
-            search_stats.cumulative_depth()
-        };
-
-        let time = search_stats.time_passesd_ms();
-
-        let nps = (nodes as u128 * 1000) / time.max(1);
-
         print!("{}\r", " ".repeat(t_width));
-        println!("{}", format!(" Nodes:      {}", number_to_string(nodes as u128).secondary(grad(18))).primary(grad(18)));
+        println!("{}", format!(" Nodes:      {}", number_to_string(result.nodes() as u128).secondary(grad(18))).primary(grad(18)));
         print!("{}\r", " ".repeat(t_width));
-        println!("{}", format!(" Time:       {}", time_to_string(time).secondary(grad(19))).primary(grad(19)));
+        println!("{}", format!(" Time:       {}", time_to_string(result.time_ms()).secondary(grad(19))).primary(grad(19)));
         print!("{}\r", " ".repeat(t_width));
-        println!("{}", format!(" Nps:        {}", number_to_string(nps).secondary(grad(20))).primary(grad(20)));
+        println!("{}", format!(" Nps:        {}", number_to_string(result.nps()).secondary(grad(20))).primary(grad(20)));
 
         print!("{}\r", " ".repeat(t_width));
         println!();
@@ -132,21 +122,18 @@ fn print_search_report<const FINAL: bool>(_: &SearchLimits, search_stats: &Searc
         height_used += 4;
     }
 
-    let draw_score = search_engine.options().draw_score() as f64 / 100.0;
-    let pv = search_engine.tree().get_best_pv(0, draw_score);
-
-    let score = pv.score();
-    let mut v = score.win_chance() - score.lose_chance();
-    let mut d = score.draw_chance();
-
-    search_engine.contempt().rescale(&mut v, &mut d, 1.0, true, search_engine.options());
+    let Some(line) = result.lines().first() else {
+        return;
+    };
 
-    let pv_score = WDLScore::new((1.0 + v - d) / 2.0, d);
+    let pv = line.pv();
+    let pv_score = line.wdl();
 
-    let score = match pv.first_node().state() {
-        engine::GameState::Loss(len) => format!("+M{}", (len + 1).div_ceil(2)),
-        engine::GameState::Win(len) => format!("-M{}", (len + 1).div_ceil(2)),
-        _ => format!("{}{:.2}", if pv_score.single() < 0.5 { "-" } else { "+" }, pv_score.cp().abs() as f32 / 100.0)
+    let score = match line.score() {
+        LineScore::Mate(moves) if moves > 0 => format!("+M{moves}"),
+        LineScore::Mate(moves) => format!("-M{}", -moves),
+        LineScore::Cp(0) => String::from("0.00"),
+        LineScore::Cp(cp) => format!("{}{:.2}", if cp < 0 { "-" } else { "+" }, cp.abs() as f32 / 100.0),
     };
 
     print!("{}\r", " ".repeat(t_width));
@@ -158,11 +145,8 @@ fn print_search_report<const FINAL: bool>(_: &SearchLimits, search_stats: &Searc
     print!("{}\r", " ".repeat(t_width));
     println!("{}", format!(" Lose:       {}", create_loading_bar(50, pv.score().lose_chance() as f32, LOSE_COLOR, LOSE_COLOR).secondary(grad(25))).primary(grad(25)));
 
-    unsafe {
-        #[allow(static_mut_refs)]
-        if SEARCH_HISTORY.len() == 0 || SEARCH_HISTORY.last().unwrap().1.first_move() != pv.first_move() {
-            SEARCH_HISTORY.push((search_stats.time_passesd_ms(), pv.clone()));
-        }
+    if search_history.last().is_none_or(|(_, last_pv)| last_pv.first_move() != pv.first_move()) {
+        search_history.push((result.time_ms(), pv.clone()));
     }
 
     let pv_string = if FINAL {
@@ -183,17 +167,12 @@ fn print_search_report<const FINAL: bool>(_: &SearchLimits, search_stats: &Searc
     print!("{}\r", " ".repeat(t_width));
     println!("{}", format!(" Search History:").primary(grad(28)));
 
-    #[allow(static_mut_refs)]
-    unsafe {
-        let start_idx = (SEARCH_HISTORY.len() as i32 - (t_height - height_used - 4) as i32).max(0) as usize;
-        for idx in start_idx..SEARCH_HISTORY.len() {
-            let (time, pv) = &SEARCH_HISTORY[idx];
+    let start_idx = (search_history.len() as i32 - (t_height - height_used - 4) as i32).max(0) as usize;
+    for (time, pv) in &search_history[start_idx..] {
+        let pv_string = pv.to_string_wrapped(PV_WRAPPING, search_engine.options().chess960());
 
-            let pv_string = pv.to_string_wrapped(PV_WRAPPING, search_engine.options().chess960());
-
-            print!("{}\r", " ".repeat(t_width));
-            println!("{}", format!("{} -> {}", time_to_string(*time).align_to_right(9), pv_string).secondary(grad(29)))
-        }
+        print!("{}\r", " ".repeat(t_width));
+        println!("{}", format!("{} -> {}", time_to_string(*time).align_to_right(9), pv_string).secondary(grad(29)))
     }
 
     print!("{}\r", " ".repeat(t_width));
@@ -1,78 +1,54 @@
-use engine::{SearchEngine, SearchLimits, SearchReport, SearchStats, WDLScore};
+use engine::{SearchEngine, SearchLimits, SearchReport, SearchResult, SearchStats};
 
 pub struct UciSearchReport;
 impl SearchReport for UciSearchReport {
-    fn refresh_rate_per_second() -> f64 {
+    fn refresh_rate_per_second(&self) -> f64 {
         1.0
     }
 
-    fn search_report(_: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) {
-        let depth = search_stats.avg_depth();
-        let max_depth = search_stats.max_depth();
-
-        let pv_count = search_engine.tree().root_node().children_count().min(search_engine.options().multi_pv() as usize);
-
-        let draw_score = search_engine.options().draw_score() as f64 / 100.0;
-
-        for pv_idx in 0..pv_count {
-            let pv = search_engine.tree().get_best_pv(pv_idx as usize, draw_score);
-
-            let score = pv.score();
-            let mut v = score.win_chance() - score.lose_chance();
-            let mut d = score.draw_chance();
+    fn tree_reused(&mut self, _: &SearchLimits, carried_visits: u32, _: &SearchEngine) {
+        println!("info string Reused {carried_visits} visits from the previous search");
+    }
 
-            search_engine.contempt().rescale(&mut v, &mut d, 1.0, true, search_engine.options());
+    fn search_report(&mut self, _: &SearchLimits, search_stats: &SearchStats, search_engine: &SearchEngine) {
+        let result = SearchResult::new(search_stats, search_engine);
 
-            let pv_score = WDLScore::new((1.0 + v - d) / 2.0, d);
+        let depth = result.avg_depth();
+        let max_depth = result.max_depth();
+        let time = result.time_ms();
+        let nodes = result.nodes();
+        let nps = result.nps();
+        let hashfull = search_engine.tree().hash_table().hashfull();
 
-            let state = pv.first_node().state();
-            let score = match state {
-                engine::GameState::Loss(len) => format!("mate {}", (len + 1).div_ceil(2)),
-                engine::GameState::Win(len) => format!("mate -{}", (len + 1).div_ceil(2)),
-                _ => format!("cp {}", pv_score.cp())
-            };
+        for (pv_idx, line) in result.lines().iter().enumerate() {
+            let score = line.score();
 
             let wdl = if search_engine.options().show_wdl() {
                 format!(" wdl {:.0} {:.0} {:.0}", 
-                    pv_score.win_chance() * 1000.0, 
-                    pv_score.draw_chance() * 1000.0,
-                    pv_score.lose_chance() * 1000.0
+                    line.wdl().win_chance() * 1000.0, 
+                    line.wdl().draw_chance() * 1000.0,
+                    line.wdl().lose_chance() * 1000.0
                 )
             } else {
                String::new() 
             };
-            
-            let time = search_stats.time_passesd_ms();
-            let nodes = if search_engine.options().report_iters() {
-                search_stats.iterations()
-            } else {
-                search_stats.cumulative_depth()
-            };
-
-            let nps = (nodes as u128 * 1000) / time.max(1);
 
-            let hashfull = search_engine.tree().current_size() * 1000 / search_engine.tree().max_size();
-
-            let pv = pv.to_string(search_engine.options().chess960());
+            let pv = line.pv().to_string(search_engine.options().chess960());
 
             println!("info depth {depth} seldepth {max_depth} score {score}{wdl} time {time} nodes {nodes} nps {nps} hashfull {hashfull} multipv {} pv {pv}", pv_idx + 1)   
         }
     }
 
-    fn search_ended(_: &SearchLimits, _: &SearchStats, search_engine: &SearchEngine) {
-        let draw_score = search_engine.options().draw_score() as f64 / 100.0;
-        let best_node_idx = search_engine.tree().select_best_child(search_engine.tree().root_index(), draw_score);
-
-        if best_node_idx.is_none() {
+    fn search_ended(&mut self, _: &SearchLimits, search_result: &SearchResult, search_engine: &SearchEngine) {
+        let Some(best_move) = search_result.best_move() else {
             return;
-        }
+        };
+
+        let chess960 = search_engine.options().chess960();
+
+        let ponder = search_result.ponder_move()
+            .map_or(String::new(), |mv| format!(" ponder {}", mv.to_string(chess960)));
 
-        println!(
-            "bestmove {}",
-            search_engine
-                .tree()[best_node_idx.unwrap()]
-                .mv()
-                .to_string(search_engine.options().chess960())
-        );
+        println!("bestmove {}{ponder}", best_move.to_string(chess960));
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,7 @@
 use std::io::Write;
 
 use chess::{ChessBoard, ChessPosition, Piece, Side, Square, DEFAULT_PERFT_DEPTH, FEN};
-use engine::{NoReport, NodeIndex, PolicyNetwork, SearchEngine, SearchLimits, ValueNetwork, WDLScore};
+use engine::{policy_network, value_network, NoReport, PolicyCache, NodeIndex, SearchEngine, SearchLimits, WDLScore};
 use utils::{clear_terminal_screen, create_loading_bar, heat_color, time_to_string, number_to_string, AlignString, Colors, CustomColor, PieceColors, Theme, DRAW_COLOR, LOSE_COLOR, WIN_COLOR};
 
 pub struct MiscProcessor;
@@ -58,6 +58,18 @@ impl MiscProcessor {
 
                 search_engine.tree().draw_tree::<false>(depth, node_idx, &search_engine);
             },
+            "savetree" => {
+                match search_engine.save_tree(&args.join(" ")) {
+                    Ok(()) => println!("Tree has been saved."),
+                    Err(msg) => eprintln!("{msg}"),
+                }
+            },
+            "loadtree" => {
+                match search_engine.load_tree(&args.join(" ")) {
+                    Ok(()) => println!("Tree has been loaded."),
+                    Err(msg) => eprintln!("{msg}"),
+                }
+            },
             "perft" => {
                 let depth = if args.len() >= 1 {
                     args[0].parse::<u8>().ok()
@@ -138,7 +150,7 @@ fn eval_bench() {
 
     for fen in FENS {
         let board = ChessBoard::from(&FEN::from(fen));
-        let wdl_score = ValueNetwork.forward(&board);
+        let wdl_score = value_network().forward(&board);
         println!("{}",
             format!("{fen}: {}", 
                 format!("[{}, {}, {}]",
@@ -156,7 +168,7 @@ fn draw_policy(search_engine: &SearchEngine) {
 
     board.draw_board();
 
-    let inputs = PolicyNetwork.get_inputs(board);
+    let inputs = policy_network().get_inputs(board);
     let mut max = f32::NEG_INFINITY;
     let mut total = 0f32;
 
@@ -164,10 +176,10 @@ fn draw_policy(search_engine: &SearchEngine) {
     let mut max_policy = f32::NEG_INFINITY;
     let mut moves = Vec::new();
 
-    let mut policy_cache: [Option<Vec<f32>>; 192] = [const { None }; 192];
+    let mut policy_cache = PolicyCache::default();
 
     board.map_legal_moves(|mv| {
-        let p = PolicyNetwork.forward(board, &inputs, mv, &mut policy_cache);
+        let p = policy_network().forward(board, &inputs, mv, &mut policy_cache);
         max = max.max(p);
         moves.push((mv, p));
     });
@@ -200,7 +212,7 @@ fn draw_policy(search_engine: &SearchEngine) {
 fn eval(search_engine: &SearchEngine) {
     let board = search_engine.root_position().board();
 
-    let wdl_score = ValueNetwork.forward(board);
+    let wdl_score = value_network().forward(board);
     let current_eval = wdl_score.cp();
 
     let mut v = wdl_score.win_chance() - wdl_score.lose_chance();
@@ -259,7 +271,7 @@ fn eval(search_engine: &SearchEngine) {
             return;
         }
 
-        evals[usize::from(square)] = ValueNetwork.forward(&board_cpy).cp();
+        evals[usize::from(square)] = value_network().forward(&board_cpy).cp();
     });
 
     println!("\n{} {}\n", " FEN:".primary(0.0), FEN::from(board).to_string().secondary(0.1));
@@ -284,7 +296,8 @@ fn analyse(search_engine: &mut SearchEngine, iters: Option<u64>) {
     print!("{} {}\r", " Progress:".primary(4.0/32.0), create_loading_bar(50, progress, (225,225,225), (225,225,225)).secondary(4.0/32.0));
     let _ = std::io::stdout().flush();
 
-    search_engine.search::<NoReport>(&search_limits);
+    //The engine is borrowed mutably, so no other search can be running on it
+    search_engine.search(&search_limits, &mut NoReport).expect("Analyse search failed to start.");
     progress += 1.0;
 
     print!("{} {}\r", " Progress:".primary(5.0/32.0), create_loading_bar(50, progress / piece_count as f32, (225,225,225), (225,225,225)).secondary(5.0/32.0));
@@ -317,7 +330,7 @@ fn analyse(search_engine: &mut SearchEngine, iters: Option<u64>) {
         }
 
         search_engine.set_position(&ChessPosition::from(board_cpy), 0);
-        search_engine.search::<NoReport>(&search_limits);
+        search_engine.search(&search_limits, &mut NoReport).expect("Analyse search failed to start.");
 
         evals[usize::from(square)] = search_engine.tree().get_best_pv(0, draw_score).score().cp();
 
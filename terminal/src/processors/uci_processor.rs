@@ -1,5 +1,5 @@
 use chess::{ChessBoard, ChessPosition, Side, FEN};
-use engine::{SearchEngine, SearchLimits};
+use engine::{GoCommand, SearchEngine, SearchLimits};
 use utils::clear_terminal_screen;
 
 use crate::{displays::{PrettySearchReport, UciSearchReport}, InputWrapper};
@@ -117,7 +117,9 @@ impl UciProcessor {
             });
         }
 
-        search_engine.tree().try_reuse(search_engine.root_position(), &chess_position, search_engine.options());
+        //Moves played since the last position, the reused node has to be exactly that deep
+        let plies = (moves.len() as u16).saturating_sub(search_engine.game_ply());
+        search_engine.tree().try_reuse(search_engine.root_position(), &chess_position, u32::from(plies), search_engine.options());
 
         search_engine.set_position(&chess_position, moves.len() as u16);
         println!("Position has been set.");
@@ -132,14 +134,20 @@ impl UciProcessor {
     ) {
         let search_limits = create_search_limits(args, search_engine.root_position().board(), search_engine);
 
+        let search_engine: &SearchEngine = search_engine;
+
         std::thread::scope(|s| {
-            s.spawn(|| {
-                let _ = if self.uci_initialized { 
-                    search_engine.search::<UciSearchReport>(&search_limits)
-                } else { 
-                    search_engine.search::<PrettySearchReport>(&search_limits)
-                };
-            });
+            //Spawned searches store the ponder token up front, so a ponderhit read below is never overwritten
+            let handle = if self.uci_initialized { 
+                search_engine.spawn_search(s, &search_limits, UciSearchReport)
+            } else { 
+                search_engine.spawn_search(s, &search_limits, PrettySearchReport::default())
+            };
+
+            if let Err(err) = handle {
+                println!("info string {err}");
+                return;
+            }
 
             loop {
                 let input_command = input_wrapper.get_input_no_queue();
@@ -147,6 +155,7 @@ impl UciProcessor {
                 match input_command.trim() {
                     "isready" => println!("readyok"),
                     "stop" => search_engine.interrupt_search(),
+                    "ponderhit" => search_engine.ponderhit(),
                     "quit" => {
                         search_engine.interrupt_search();
                         *shutdown_token = true
@@ -167,94 +176,51 @@ impl UciProcessor {
 }
 
 fn create_search_limits(args: &[String], board: &ChessBoard, search_engine: &SearchEngine) -> SearchLimits {
-    let mut search_limits = SearchLimits::default();
+    let (go, diagnostics) = GoCommand::parse(args);
+    for diagnostic in diagnostics {
+        println!("info string {diagnostic}");
+    }
 
-    let mut iters = None;
-    let mut depth = None;
-    let mut move_time = None;
-    let mut moves_to_go = None;
-    let (mut wtime, mut btime, mut winc, mut binc) = (None, None, None, None);
-    let mut infinite = false;
-
-    for (idx, arg) in args.iter().enumerate() {
-        match arg.as_str() {
-            "infinite" => infinite = true,
-            "nodes" => {
-                iters = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u64>().ok()
-                } else {
-                    None
-                }
-            }
-            "depth" => {
-                depth = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u64>().ok()
-                } else {
-                    None
-                }
-            }
-            "movetime" => {
-                move_time = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u128>().ok()
-                } else {
-                    None
-                }
-            }
-            "moves_to_go" => {
-                moves_to_go = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u128>().ok()
-                } else {
-                    None
-                }
-            }
-            "wtime" => {
-                wtime = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u128>().ok()
-                } else {
-                    None
-                }
-            }
-            "btime" => {
-                btime = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u128>().ok()
-                } else {
-                    None
-                }
-            }
-            "winc" => {
-                winc = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u128>().ok()
-                } else {
-                    None
-                }
-            }
-            "binc" => {
-                binc = if args.len() > idx + 1 {
-                    args[idx + 1].parse::<u128>().ok()
-                } else {
-                    None
-                }
+    let mut search_moves = Vec::new();
+    for mv in go.search_moves() {
+        let mut legal = false;
+        board.map_legal_moves(|legal_mv| {
+            if *mv == legal_mv.to_string(search_engine.options().chess960()) {
+                search_moves.push(legal_mv);
+                legal = true;
             }
-            _ => continue,
+        });
+
+        if !legal {
+            println!("info string Ignoring illegal searchmove '{mv}'");
         }
     }
 
-    search_limits.set_iters(iters);
-    search_limits.set_depth(depth);
-    search_limits.set_infinite(infinite);
+    if go.nodes().is_some() {
+        println!("info string nodes limits MCTS iterations");
+    }
+
+    let mut search_limits = SearchLimits::default();
+
+    search_limits.set_iters(go.nodes());
+    search_limits.set_depth(go.depth());
+    search_limits.set_mate(go.mate());
+    search_limits.set_search_moves(search_moves);
+    search_limits.set_infinite(go.infinite());
+    search_limits.set_ponder(go.ponder());
 
-    if let Some(move_time) = move_time {
+    if let Some(move_time) = go.move_time() {
         search_limits.set_time(move_time);
         return search_limits;
     }
 
     let (time_remaining, increment) = if board.side() == Side::WHITE {
-        (wtime, winc)
+        (go.wtime(), go.winc())
     } else {
-        (btime, binc)
+        (go.btime(), go.binc())
     };
 
-    search_limits.calculate_time_limit(time_remaining, increment, moves_to_go, search_engine.options(), search_engine.game_ply(), board.phase() as f64);
+    search_limits.calculate_time_limit(time_remaining, increment, go.moves_to_go(), search_engine.options(), search_engine.game_ply(), board.phase() as f64);
 
     search_limits
 }
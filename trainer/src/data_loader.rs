@@ -0,0 +1,38 @@
+use std::{fs::File, io::BufReader};
+
+use chess::{GameData, PackedBoard, PolicyData};
+
+//Unpacks every recorded position from a datagen game file.
+//Positions in check are skipped, the search score there is rarely meaningful for a static evaluation.
+pub fn load_value_data(path: &str) -> std::io::Result<Vec<PackedBoard>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut result = Vec::new();
+
+    while let Some(game) = GameData::read_from(&mut reader)? {
+        game.map_positions(|board, _, score| {
+            if board.is_in_check() {
+                return;
+            }
+
+            result.push(PackedBoard::from_board(board, score, game.result()));
+        });
+    }
+
+    Ok(result)
+}
+
+//Reads every policy record, positions with a single legal move carry no information and are skipped.
+pub fn load_policy_data(path: &str) -> std::io::Result<Vec<PolicyData>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut result = Vec::new();
+
+    while let Some(record) = PolicyData::read_from(&mut reader)? {
+        if record.visits().len() < 2 || record.visits().iter().all(|&(_, visits)| visits == 0) {
+            continue;
+        }
+
+        result.push(record);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,14 @@
+mod data_loader;
+mod optimizer;
+mod policy_trainer;
+mod trainer_options;
+mod training;
+mod value_trainer;
+
+pub use policy_trainer::train_policy;
+pub use policy_trainer::PolicyNetwork;
+pub use trainer_options::TrainerOptions;
+pub use training::network_bytes;
+pub use training::TrainableNetwork;
+pub use value_trainer::train_value;
+pub use value_trainer::ValueNetwork;
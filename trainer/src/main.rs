@@ -1 +1,20 @@
-fn main() {}
+use trainer::{train_policy, train_value, TrainerOptions};
+
+fn main() {
+    let args = std::env::args().collect::<Vec<String>>();
+    let options = TrainerOptions::from_args(&args[1..]);
+
+    println!("Threads:      {}", options.threads());
+    println!("Data:         {}", options.data());
+    println!("Output:       {}", options.output());
+
+    match args.get(1).map(String::as_str) {
+        Some("value") => train_value(&options),
+        Some("policy") => train_policy(&options),
+        Some("quantise") => match engine::quantise_policy_file(options.data(), options.output()) {
+            Ok(()) => println!("Saved '{}'", options.output()),
+            Err(err) => println!("{err}"),
+        },
+        _ => println!("Usage: trainer <value|policy|quantise> [data <path>] [output <name>] [threads <n>] ..."),
+    }
+}
@@ -0,0 +1,36 @@
+const BETA1: f32 = 0.9;
+const BETA2: f32 = 0.999;
+const EPSILON: f32 = 1e-8;
+const DECAY: f32 = 0.01;
+
+//Quantised weights have to fit into i16 after scaling, so every parameter is clipped to this range.
+pub const MAX_WEIGHT: f32 = 1.98;
+
+pub struct AdamW {
+    momentum: Vec<f32>,
+    velocity: Vec<f32>,
+}
+
+impl AdamW {
+    pub fn new(size: usize) -> Self {
+        Self {
+            momentum: vec![0.0; size],
+            velocity: vec![0.0; size],
+        }
+    }
+
+    pub fn step(&mut self, params: &mut [f32], gradients: &[f32], lr: f32, batch_size: usize) {
+        let scale = 1.0 / batch_size as f32;
+
+        for (idx, param) in params.iter_mut().enumerate() {
+            let gradient = gradients[idx] * scale;
+
+            self.momentum[idx] = BETA1 * self.momentum[idx] + (1.0 - BETA1) * gradient;
+            self.velocity[idx] = BETA2 * self.velocity[idx] + (1.0 - BETA2) * gradient * gradient;
+
+            *param *= 1.0 - lr * DECAY;
+            *param -= lr * self.momentum[idx] / (self.velocity[idx].sqrt() + EPSILON);
+            *param = param.clamp(-MAX_WEIGHT, MAX_WEIGHT);
+        }
+    }
+}
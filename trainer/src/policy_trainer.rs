@@ -0,0 +1,29 @@
+use utils::{number_to_string, Random};
+
+use crate::{data_loader::load_policy_data, trainer_options::TrainerOptions, training::train};
+
+pub use self::policy_network::PolicyNetwork;
+
+mod policy_network;
+
+pub fn train_policy(options: &TrainerOptions) {
+    let records = match load_policy_data(options.data()) {
+        Ok(records) => records,
+        Err(err) => {
+            println!("Failed to load '{}': {err}", options.data());
+            return;
+        }
+    };
+
+    if records.is_empty() {
+        println!("No policy records found in '{}'", options.data());
+        return;
+    }
+
+    println!("Positions:    {}", number_to_string(records.len() as u128));
+
+    let mut random = Random::new(0);
+    let mut network = PolicyNetwork::new(&mut random);
+
+    train(options, &mut network, &records, &mut random);
+}
@@ -0,0 +1,228 @@
+use chess::{ChessBoard, Move, PolicyData, Side};
+use engine::{NetworkHeader, Standard768};
+use utils::Random;
+
+use crate::training::TrainableNetwork;
+
+const INPUT_SIZE: usize = Standard768::input_size();
+const HIDDEN_SIZE: usize = 32;
+const SUBNET_COUNT: usize = 192;
+
+//SEE threshold splitting the to-square subnets into losing and non-losing captures, same as the engine
+const SEE_THRESHOLD: i32 = -108;
+
+//Flat layout of a single subnet, matches PolicyNetworkSubnet
+const L0_WEIGHTS: usize = 0;
+const L0_BIASES: usize = L0_WEIGHTS + INPUT_SIZE * HIDDEN_SIZE;
+const L1_WEIGHTS: usize = L0_BIASES + HIDDEN_SIZE;
+const L1_BIASES: usize = L1_WEIGHTS + HIDDEN_SIZE * HIDDEN_SIZE;
+const SUBNET_SIZE: usize = L1_BIASES + HIDDEN_SIZE;
+
+#[derive(Clone, Copy)]
+struct SubnetOutput {
+    hidden: [f32; HIDDEN_SIZE],
+    out: [f32; HIDDEN_SIZE],
+}
+
+pub struct PolicyNetwork {
+    params: Vec<f32>,
+}
+
+impl PolicyNetwork {
+    pub fn new(random: &mut Random) -> Self {
+        let mut params = vec![0.0; SUBNET_COUNT * SUBNET_SIZE];
+        let range = 1.0 / (HIDDEN_SIZE as f32).sqrt();
+
+        for subnet in params.chunks_mut(SUBNET_SIZE) {
+            for weight in &mut subnet[L0_WEIGHTS..L0_BIASES] {
+                *weight = (random.next_f32() * 2.0 - 1.0) * range;
+            }
+
+            for weight in &mut subnet[L1_WEIGHTS..L1_BIASES] {
+                *weight = (random.next_f32() * 2.0 - 1.0) * range;
+            }
+        }
+
+        Self { params }
+    }
+
+    //Logit of mv before the softmax, what the exported network evaluates to
+    pub fn forward(&self, board: &ChessBoard, mv: Move) -> f32 {
+        let mut inputs = Vec::with_capacity(board.occupancy().pop_count() as usize);
+        Standard768::map_inputs(board, |idx| inputs.push(idx));
+
+        let (from_idx, to_idx) = subnet_indices(board, mv);
+        let from = self.subnet_forward(from_idx, &inputs);
+        let to = self.subnet_forward(to_idx, &inputs);
+
+        from.out.iter().zip(&to.out).map(|(&a, &b)| relu(a) * relu(b)).sum::<f32>()
+    }
+
+    fn subnet_forward(&self, subnet_idx: usize, inputs: &[usize]) -> SubnetOutput {
+        let subnet = &self.params[subnet_idx * SUBNET_SIZE..][..SUBNET_SIZE];
+
+        let mut hidden = [0.0; HIDDEN_SIZE];
+        hidden.copy_from_slice(&subnet[L0_BIASES..L1_WEIGHTS]);
+
+        for &input in inputs {
+            for (neuron, &weight) in hidden.iter_mut().zip(&subnet[L0_WEIGHTS + input * HIDDEN_SIZE..][..HIDDEN_SIZE]) {
+                *neuron += weight;
+            }
+        }
+
+        let mut out = [0.0; HIDDEN_SIZE];
+        out.copy_from_slice(&subnet[L1_BIASES..]);
+
+        for (idx, &neuron) in hidden.iter().enumerate() {
+            let activated = relu(neuron);
+            for (output, &weight) in out.iter_mut().zip(&subnet[L1_WEIGHTS + idx * HIDDEN_SIZE..][..HIDDEN_SIZE]) {
+                *output += activated * weight;
+            }
+        }
+
+        SubnetOutput { hidden, out }
+    }
+
+    fn subnet_backward(&self, subnet_idx: usize, inputs: &[usize], output: &SubnetOutput, out_gradients: &[f32; HIDDEN_SIZE], gradients: &mut [f32]) {
+        let subnet = &self.params[subnet_idx * SUBNET_SIZE..][..SUBNET_SIZE];
+        let gradients = &mut gradients[subnet_idx * SUBNET_SIZE..][..SUBNET_SIZE];
+
+        for (gradient, &out_gradient) in gradients[L1_BIASES..].iter_mut().zip(out_gradients) {
+            *gradient += out_gradient;
+        }
+
+        let mut hidden_gradients = [0.0; HIDDEN_SIZE];
+        for (idx, &neuron) in output.hidden.iter().enumerate() {
+            if neuron <= 0.0 {
+                continue;
+            }
+
+            let row = L1_WEIGHTS + idx * HIDDEN_SIZE;
+            for (out_idx, &out_gradient) in out_gradients.iter().enumerate() {
+                gradients[row + out_idx] += neuron * out_gradient;
+                hidden_gradients[idx] += subnet[row + out_idx] * out_gradient;
+            }
+        }
+
+        for (gradient, &hidden_gradient) in gradients[L0_BIASES..L1_WEIGHTS].iter_mut().zip(&hidden_gradients) {
+            *gradient += hidden_gradient;
+        }
+
+        for &input in inputs {
+            let row = &mut gradients[L0_WEIGHTS + input * HIDDEN_SIZE..][..HIDDEN_SIZE];
+            for (gradient, &hidden_gradient) in row.iter_mut().zip(&hidden_gradients) {
+                *gradient += hidden_gradient;
+            }
+        }
+    }
+}
+
+impl TrainableNetwork for PolicyNetwork {
+    type Sample = PolicyData;
+
+    #[inline]
+    fn params_mut(&mut self) -> &mut [f32] {
+        &mut self.params
+    }
+
+    //Softmax cross-entropy over the legal moves against the normalised root visit distribution.
+    fn backward(&self, sample: &PolicyData, gradients: &mut [f32]) -> f32 {
+        let board = sample.board();
+
+        let mut inputs = Vec::with_capacity(board.occupancy().pop_count() as usize);
+        Standard768::map_inputs(&board, |idx| inputs.push(idx));
+
+        let mut cache: [Option<SubnetOutput>; SUBNET_COUNT] = [None; SUBNET_COUNT];
+
+        let mut subnets = Vec::with_capacity(sample.visits().len());
+        let mut logits = Vec::with_capacity(sample.visits().len());
+
+        for &(mv, _) in sample.visits() {
+            let (from_idx, to_idx) = subnet_indices(&board, mv);
+
+            for idx in [from_idx, to_idx] {
+                if cache[idx].is_none() {
+                    cache[idx] = Some(self.subnet_forward(idx, &inputs));
+                }
+            }
+
+            let from = cache[from_idx].as_ref().unwrap();
+            let to = cache[to_idx].as_ref().unwrap();
+
+            subnets.push((from_idx, to_idx));
+            logits.push(from.out.iter().zip(&to.out).map(|(&a, &b)| relu(a) * relu(b)).sum::<f32>());
+        }
+
+        let max = logits.iter().fold(f32::NEG_INFINITY, |max, &logit| max.max(logit));
+        let mut total = 0.0;
+        for logit in logits.iter_mut() {
+            *logit = (*logit - max).exp();
+            total += *logit;
+        }
+
+        let visit_total = sample.visits().iter().map(|&(_, visits)| f32::from(visits)).sum::<f32>();
+
+        let mut loss = 0.0;
+        let mut out_gradients = [[0.0f32; HIDDEN_SIZE]; SUBNET_COUNT];
+
+        for ((&(_, visits), &(from_idx, to_idx)), &exp) in sample.visits().iter().zip(&subnets).zip(&logits) {
+            let probability = exp / total;
+            let target = f32::from(visits) / visit_total;
+
+            if target > 0.0 {
+                loss -= target * probability.max(f32::EPSILON).ln();
+            }
+
+            let gradient = probability - target;
+            let from = cache[from_idx].as_ref().unwrap();
+            let to = cache[to_idx].as_ref().unwrap();
+
+            for (idx, (&from_out, &to_out)) in from.out.iter().zip(&to.out).enumerate() {
+                if from_out > 0.0 && to_out > 0.0 {
+                    out_gradients[from_idx][idx] += gradient * to_out;
+                    out_gradients[to_idx][idx] += gradient * from_out;
+                }
+            }
+        }
+
+        for (subnet_idx, output) in cache.iter().enumerate() {
+            if let Some(output) = output {
+                self.subnet_backward(subnet_idx, &inputs, output, &out_gradients[subnet_idx], gradients);
+            }
+        }
+
+        loss
+    }
+
+    //The engine keeps the policy in f32, so the export is the raw parameters in PolicyNetwork order.
+    fn export(&self) -> Result<Vec<u8>, String> {
+        let mut result = Vec::with_capacity(std::mem::size_of_val(&engine::PolicyNetwork));
+
+        for &value in &self.params {
+            result.extend_from_slice(&value.to_le_bytes());
+        }
+
+        assert_eq!(result.len(), std::mem::size_of_val(&engine::PolicyNetwork));
+        Ok(result)
+    }
+
+    #[inline]
+    fn header(payload: &[u8]) -> NetworkHeader {
+        NetworkHeader::policy(payload)
+    }
+}
+
+//From-square subnet and to-square subnet split by SEE, seen from the side to move
+fn subnet_indices(board: &ChessBoard, mv: Move) -> (usize, usize) {
+    let vertical_flip = (usize::from(board.side() == Side::BLACK) * 56) as u8;
+    let see_idx = usize::from(board.see(mv, SEE_THRESHOLD));
+
+    let from_idx = usize::from(mv.get_from_square() ^ vertical_flip);
+    let to_idx = usize::from(mv.get_to_square() ^ vertical_flip) + 64 + see_idx * 64;
+    (from_idx, to_idx)
+}
+
+#[inline]
+fn relu(x: f32) -> f32 {
+    x.max(0.0)
+}
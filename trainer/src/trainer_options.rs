@@ -0,0 +1,106 @@
+#[derive(Debug, Clone)]
+pub struct TrainerOptions {
+    threads: usize,
+    data: String,
+    output: String,
+    superbatches: usize,
+    batches_per_superbatch: usize,
+    batch_size: usize,
+    lr: f32,
+    final_lr: f32,
+    wdl: f32,
+    save_rate: usize,
+}
+
+impl Default for TrainerOptions {
+    fn default() -> Self {
+        Self {
+            threads: 1,
+            data: String::from("data.bin"),
+            output: String::from("network"),
+            superbatches: 600,
+            batches_per_superbatch: 6104,
+            batch_size: 16384,
+            lr: 0.001,
+            final_lr: 0.00001,
+            wdl: 1.0,
+            save_rate: 50,
+        }
+    }
+}
+
+impl TrainerOptions {
+    pub fn from_args(args: &[String]) -> Self {
+        let mut result = Self::default();
+
+        for (idx, arg) in args.iter().enumerate() {
+            let value = if args.len() > idx + 1 {
+                args[idx + 1].as_str()
+            } else {
+                continue;
+            };
+
+            match arg.as_str() {
+                "threads" => result.threads = value.parse::<usize>().unwrap_or(result.threads).max(1),
+                "data" => result.data = value.to_string(),
+                "output" => result.output = value.to_string(),
+                "superbatches" => result.superbatches = value.parse::<usize>().unwrap_or(result.superbatches).max(1),
+                "batches" => result.batches_per_superbatch = value.parse::<usize>().unwrap_or(result.batches_per_superbatch).max(1),
+                "batch_size" => result.batch_size = value.parse::<usize>().unwrap_or(result.batch_size).max(1),
+                "lr" => result.lr = value.parse::<f32>().unwrap_or(result.lr),
+                "final_lr" => result.final_lr = value.parse::<f32>().unwrap_or(result.final_lr),
+                "wdl" => result.wdl = value.parse::<f32>().unwrap_or(result.wdl).clamp(0.0, 1.0),
+                "save_rate" => result.save_rate = value.parse::<usize>().unwrap_or(result.save_rate).max(1),
+                _ => continue,
+            }
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    #[inline]
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    #[inline]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    #[inline]
+    pub fn superbatches(&self) -> usize {
+        self.superbatches
+    }
+
+    #[inline]
+    pub fn batches_per_superbatch(&self) -> usize {
+        self.batches_per_superbatch
+    }
+
+    #[inline]
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    #[inline]
+    pub fn wdl(&self) -> f32 {
+        self.wdl
+    }
+
+    #[inline]
+    pub fn save_rate(&self) -> usize {
+        self.save_rate
+    }
+
+    //Cosine decay from lr to final_lr across all superbatches.
+    pub fn lr(&self, superbatch: usize) -> f32 {
+        let progress = superbatch as f32 / self.superbatches as f32;
+        self.final_lr + 0.5 * (self.lr - self.final_lr) * (1.0 + (std::f32::consts::PI * progress).cos())
+    }
+}
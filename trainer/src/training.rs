@@ -0,0 +1,98 @@
+use std::{fs, time::Instant};
+
+use engine::NetworkHeader;
+use utils::{time_to_string, Random};
+
+use crate::{optimizer::AdamW, trainer_options::TrainerOptions};
+
+pub trait TrainableNetwork: Sync {
+    type Sample: Sync;
+
+    fn params_mut(&mut self) -> &mut [f32];
+
+    //Accumulates gradients of the loss on a single sample and returns the loss.
+    fn backward(&self, sample: &Self::Sample, gradients: &mut [f32]) -> f32;
+
+    //Byte image of the network in the layout the engine transmutes.
+    fn export(&self) -> Result<Vec<u8>, String>;
+
+    //Header the engine loader checks the exported payload against.
+    fn header(payload: &[u8]) -> NetworkHeader;
+}
+
+pub fn train<N: TrainableNetwork>(options: &TrainerOptions, network: &mut N, samples: &[N::Sample], random: &mut Random) {
+    let parameter_count = network.params_mut().len();
+    let mut optimizer = AdamW::new(parameter_count);
+
+    let threads = options.threads();
+    let mut thread_gradients = vec![vec![0.0f32; parameter_count]; threads];
+    let mut batch = Vec::with_capacity(options.batch_size());
+
+    let timer = Instant::now();
+
+    for superbatch in 1..=options.superbatches() {
+        let lr = options.lr(superbatch - 1);
+        let mut superbatch_loss = 0.0;
+
+        for _ in 0..options.batches_per_superbatch() {
+            batch.clear();
+            for _ in 0..options.batch_size() {
+                batch.push(&samples[random.range(samples.len())]);
+            }
+
+            let chunk_size = batch.len().div_ceil(threads);
+            let chunk_count = batch.len().div_ceil(chunk_size);
+            let network_ref = &*network;
+
+            let loss = std::thread::scope(|s| {
+                let handles = batch
+                    .chunks(chunk_size)
+                    .zip(thread_gradients.iter_mut())
+                    .map(|(chunk, gradients)| {
+                        s.spawn(move || {
+                            gradients.fill(0.0);
+                            chunk.iter().map(|&sample| network_ref.backward(sample, gradients)).sum::<f32>()
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                handles.into_iter().map(|handle| handle.join().unwrap()).sum::<f32>()
+            });
+
+            let (gradients, rest) = thread_gradients.split_first_mut().unwrap();
+            for other in rest.iter().take(chunk_count - 1) {
+                for (gradient, &value) in gradients.iter_mut().zip(other.iter()) {
+                    *gradient += value;
+                }
+            }
+
+            optimizer.step(network.params_mut(), gradients, lr, batch.len());
+            superbatch_loss += loss / batch.len() as f32;
+        }
+
+        println!(
+            "Superbatch {superbatch}/{} | loss {:.5} | lr {lr:.6} | {}",
+            options.superbatches(),
+            superbatch_loss / options.batches_per_superbatch() as f32,
+            time_to_string(timer.elapsed().as_millis())
+        );
+
+        if superbatch % options.save_rate() == 0 || superbatch == options.superbatches() {
+            let path = format!("{}-{superbatch}.network", options.output());
+
+            match network_bytes(&*network).and_then(|bytes| fs::write(&path, bytes).map_err(|err| err.to_string())) {
+                Ok(()) => println!("Saved '{path}'"),
+                Err(err) => println!("Failed to save '{path}': {err}"),
+            }
+        }
+    }
+}
+
+//Header followed by the exported network, the contents of a network file.
+pub fn network_bytes<N: TrainableNetwork>(network: &N) -> Result<Vec<u8>, String> {
+    let payload = network.export()?;
+
+    let mut bytes = N::header(&payload).to_bytes().to_vec();
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
@@ -0,0 +1,58 @@
+use chess::{PackedBoard, Side};
+use utils::{number_to_string, Random};
+
+use crate::{data_loader::load_value_data, trainer_options::TrainerOptions, training::train};
+
+pub use self::value_network::ValueNetwork;
+
+mod value_network;
+
+//Scale of the sigmoid that maps centipawns back to a win probability, mirrors WDLScore::cp
+const CP_SCALE: f32 = 246.631;
+
+pub struct ValueSample {
+    position: PackedBoard,
+    target: [f32; 3],
+}
+
+pub fn train_value(options: &TrainerOptions) {
+    let positions = match load_value_data(options.data()) {
+        Ok(positions) => positions,
+        Err(err) => {
+            println!("Failed to load '{}': {err}", options.data());
+            return;
+        }
+    };
+
+    if positions.is_empty() {
+        println!("No positions found in '{}'", options.data());
+        return;
+    }
+
+    println!("Positions:    {}", number_to_string(positions.len() as u128));
+
+    let samples = positions
+        .iter()
+        .map(|position| ValueSample { position: *position, target: target(position, options.wdl()) })
+        .collect::<Vec<_>>();
+
+    let mut random = Random::new(0);
+    let mut network = ValueNetwork::new(&mut random);
+
+    train(options, &mut network, &samples, &mut random);
+}
+
+//Side to move relative [loss, draw, win] target, blending the game result with the search score.
+fn target(position: &PackedBoard, wdl: f32) -> [f32; 3] {
+    let (score, result) = if position.side() == Side::WHITE {
+        (position.score(), position.result())
+    } else {
+        (-position.score(), PackedBoard::WHITE_WIN - position.result())
+    };
+
+    let win_chance = 1.0 / (1.0 + (-f32::from(score) / CP_SCALE).exp());
+
+    let mut target = [(1.0 - win_chance) * (1.0 - wdl), 0.0, win_chance * (1.0 - wdl)];
+    target[usize::from(result)] += wdl;
+    target
+}
@@ -0,0 +1,216 @@
+use chess::ChessBoard;
+use engine::{NetworkHeader, Threats3072};
+use utils::Random;
+
+use crate::training::TrainableNetwork;
+
+use super::ValueSample;
+
+pub const INPUT_SIZE: usize = Threats3072::input_size();
+pub const L1_SIZE: usize = 3072;
+pub const NUM_OUTPUT_BUCKETS: usize = 8;
+pub const OUTPUT_SIZE: usize = 3 * NUM_OUTPUT_BUCKETS;
+
+pub const QA: f32 = 255.0;
+pub const QB: f32 = 64.0;
+
+//Flat parameter layout, matches the order ValueNetwork stores its layers in
+const L0_WEIGHTS: usize = 0;
+const L0_BIASES: usize = L0_WEIGHTS + INPUT_SIZE * L1_SIZE;
+const L1_WEIGHTS: usize = L0_BIASES + L1_SIZE;
+const L1_BIASES: usize = L1_WEIGHTS + OUTPUT_SIZE * L1_SIZE;
+pub const PARAMETER_COUNT: usize = L1_BIASES + OUTPUT_SIZE;
+
+pub struct ValueNetwork {
+    params: Vec<f32>,
+}
+
+impl ValueNetwork {
+    pub fn new(random: &mut Random) -> Self {
+        let mut params = vec![0.0; PARAMETER_COUNT];
+
+        let l0_range = 1.0 / (32.0f32).sqrt();
+        let l1_range = 1.0 / (L1_SIZE as f32).sqrt();
+
+        for weight in &mut params[L0_WEIGHTS..L0_BIASES] {
+            *weight = (random.next_f32() * 2.0 - 1.0) * l0_range;
+        }
+
+        for weight in &mut params[L1_WEIGHTS..L1_BIASES] {
+            *weight = (random.next_f32() * 2.0 - 1.0) * l1_range;
+        }
+
+        Self { params }
+    }
+
+    //[loss, draw, win] probabilities from the side to move, what the exported network evaluates to.
+    pub fn forward(&self, board: &ChessBoard) -> [f32; 3] {
+        let mut features = Vec::with_capacity(128);
+        Threats3072::map_inputs(board, |input_index| features.push(input_index));
+
+        let activated = self.hidden(&features).map(screlu);
+        softmax(&self.logits(output_bucket(board) * 3, &activated))
+    }
+
+    //Step the parameter at idx is quantised with on export
+    pub fn quantisation_scale(idx: usize) -> f32 {
+        if idx < L1_WEIGHTS {
+            QA
+        } else if idx < L1_BIASES {
+            QB
+        } else {
+            QA * QB
+        }
+    }
+
+    fn hidden(&self, features: &[usize]) -> [f32; L1_SIZE] {
+        let mut hidden = [0.0f32; L1_SIZE];
+        hidden.copy_from_slice(&self.params[L0_BIASES..L1_WEIGHTS]);
+
+        for &feature in features {
+            let weights = &self.params[L0_WEIGHTS + feature * L1_SIZE..][..L1_SIZE];
+            for (neuron, &weight) in hidden.iter_mut().zip(weights) {
+                *neuron += weight;
+            }
+        }
+
+        hidden
+    }
+
+    fn logits(&self, bucket_idx: usize, activated: &[f32; L1_SIZE]) -> [f32; 3] {
+        let mut logits = [0.0f32; 3];
+        for (idx, logit) in logits.iter_mut().enumerate() {
+            let weights = &self.params[L1_WEIGHTS + (bucket_idx + idx) * L1_SIZE..][..L1_SIZE];
+            *logit = self.params[L1_BIASES + bucket_idx + idx]
+                + weights.iter().zip(activated.iter()).map(|(&weight, &neuron)| weight * neuron).sum::<f32>();
+        }
+
+        logits
+    }
+}
+
+impl TrainableNetwork for ValueNetwork {
+    type Sample = ValueSample;
+
+    #[inline]
+    fn params_mut(&mut self) -> &mut [f32] {
+        &mut self.params
+    }
+
+    //Softmax cross-entropy of the bucket WDL logits against the sample target.
+    fn backward(&self, sample: &ValueSample, gradients: &mut [f32]) -> f32 {
+        let board = ChessBoard::from(&sample.position);
+        let target = &sample.target;
+
+        let mut features = Vec::with_capacity(128);
+        Threats3072::map_inputs(&board, |input_index| features.push(input_index));
+
+        let hidden = self.hidden(&features);
+        let activated = hidden.map(screlu);
+
+        let bucket_idx = output_bucket(&board) * 3;
+        let logits = self.logits(bucket_idx, &activated);
+
+        let probabilities = softmax(&logits);
+
+        let mut loss = 0.0;
+        let mut logit_gradients = [0.0f32; 3];
+        for idx in 0..3 {
+            loss -= target[idx] * probabilities[idx].max(f32::EPSILON).ln();
+            logit_gradients[idx] = probabilities[idx] - target[idx];
+        }
+
+        let mut hidden_gradients = [0.0f32; L1_SIZE];
+        for (idx, &logit_gradient) in logit_gradients.iter().enumerate() {
+            let row = L1_WEIGHTS + (bucket_idx + idx) * L1_SIZE;
+            gradients[L1_BIASES + bucket_idx + idx] += logit_gradient;
+
+            for neuron in 0..L1_SIZE {
+                gradients[row + neuron] += logit_gradient * activated[neuron];
+                hidden_gradients[neuron] += logit_gradient * self.params[row + neuron];
+            }
+        }
+
+        for (gradient, &neuron) in hidden_gradients.iter_mut().zip(hidden.iter()) {
+            *gradient *= screlu_derivative(neuron);
+        }
+
+        for (gradient, &hidden_gradient) in gradients[L0_BIASES..L1_WEIGHTS].iter_mut().zip(hidden_gradients.iter()) {
+            *gradient += hidden_gradient;
+        }
+
+        for &feature in features.iter() {
+            let row = &mut gradients[L0_WEIGHTS + feature * L1_SIZE..][..L1_SIZE];
+            for (gradient, &hidden_gradient) in row.iter_mut().zip(hidden_gradients.iter()) {
+                *gradient += hidden_gradient;
+            }
+        }
+
+        loss
+    }
+
+    //Quantises into the exact byte layout of engine ValueNetwork (l0 weights, l0 biases, l1 weights, l1 biases),
+    //padded to the struct alignment so it can be transmuted without changes. Fails when a value has to be clamped.
+    fn export(&self) -> Result<Vec<u8>, String> {
+        let size = std::mem::size_of_val(&engine::ValueNetwork);
+        let mut result = Vec::with_capacity(size);
+        let mut clamped = 0;
+
+        let mut push = |value: f32, scale: f32| {
+            let rounded = (value * scale).round();
+            let quantised = rounded.clamp(i16::MIN as f32, i16::MAX as f32);
+
+            clamped += usize::from(quantised != rounded);
+            result.extend_from_slice(&(quantised as i16).to_le_bytes());
+        };
+
+        for (idx, &value) in self.params.iter().enumerate() {
+            push(value, Self::quantisation_scale(idx));
+        }
+
+        if clamped > 0 {
+            return Err(format!("{clamped} values outside of the quantised range"));
+        }
+
+        //Only the padding after the last layer may be missing
+        assert_eq!(
+            result.len().next_multiple_of(std::mem::align_of_val(&engine::ValueNetwork)),
+            size,
+            "parameter layout does not match engine ValueNetwork"
+        );
+
+        result.resize(size, 0);
+        Ok(result)
+    }
+
+    #[inline]
+    fn header(payload: &[u8]) -> NetworkHeader {
+        NetworkHeader::value(payload)
+    }
+}
+
+fn output_bucket(board: &ChessBoard) -> usize {
+    let divisor = 32usize.div_ceil(NUM_OUTPUT_BUCKETS);
+    (board.occupancy().pop_count() as usize - 2) / divisor
+}
+
+fn softmax(logits: &[f32; 3]) -> [f32; 3] {
+    let max = logits[0].max(logits[1]).max(logits[2]);
+    let exps = logits.map(|logit| (logit - max).exp());
+    let sum = exps.iter().sum::<f32>();
+    exps.map(|value| value / sum)
+}
+
+#[inline]
+fn screlu(x: f32) -> f32 {
+    x.clamp(0.0, 1.0).powi(2)
+}
+
+#[inline]
+fn screlu_derivative(x: f32) -> f32 {
+    if x > 0.0 && x < 1.0 {
+        2.0 * x
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,120 @@
+use chess::{ChessBoard, FEN};
+use engine::{load_policy_network, load_value_network, policy_network, value_network, NetworkHeader, PolicyCache};
+use trainer::{network_bytes, PolicyNetwork, TrainableNetwork, ValueNetwork};
+use utils::Random;
+
+//Fixed xorshift sequence in -1..1, so every run exports the same weights
+fn random_values(count: usize) -> impl Iterator<Item = f32> {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    (0..count).map(move |_| {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    })
+}
+
+fn write_network(name: &str, bytes: &[u8]) -> String {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, bytes).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+const FENS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+];
+
+#[test]
+fn value_export_round_trip() {
+    let mut network = ValueNetwork::new(&mut Random::new(0));
+
+    //Values on the quantisation steps survive the export unchanged, so the engine has to match the trainer
+    for ((idx, param), random) in network.params_mut().iter_mut().enumerate().zip(random_values(usize::MAX)) {
+        let scale = ValueNetwork::quantisation_scale(idx);
+        let steps = (0.15 * scale).floor();
+        *param = (random * steps).round() / scale;
+    }
+
+    let bytes = network_bytes(&network).unwrap();
+    assert_eq!(bytes.len(), NetworkHeader::SIZE + std::mem::size_of_val(&engine::ValueNetwork));
+
+    let header = NetworkHeader::from_bytes(&bytes).unwrap();
+    assert_eq!(header.architecture(), NetworkHeader::VALUE_ARCHITECTURE);
+    assert_eq!(header.payload_size(), bytes.len() - NetworkHeader::SIZE);
+
+    let path = write_network("jackal_test_trainer_value.network", &bytes);
+    load_value_network(&path).unwrap();
+
+    for fen in FENS {
+        let board = ChessBoard::from(&FEN::from(fen));
+        let [loss, draw, win] = network.forward(&board);
+        let result = value_network().forward(&board);
+
+        for (expected, result) in [(win, result.win_chance()), (draw, result.draw_chance()), (loss, result.lose_chance())] {
+            assert!((f64::from(expected) - result).abs() < 1e-4, "{fen}: {result} != {expected}");
+        }
+    }
+
+    load_value_network("").unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+//Steps the engine quantises a policy subnet with on load, l0 by QA, l1 weights by QB and l1 biases by QA * QB
+fn policy_quantisation_scale(idx: usize) -> f32 {
+    const L1_WEIGHTS: usize = 768 * 32 + 32;
+    const L1_BIASES: usize = L1_WEIGHTS + 32 * 32;
+
+    match idx % (L1_BIASES + 32) {
+        idx if idx < L1_WEIGHTS => 255.0,
+        idx if idx < L1_BIASES => 64.0,
+        _ => 255.0 * 64.0,
+    }
+}
+
+#[test]
+fn policy_export_round_trip() {
+    let mut network = PolicyNetwork::new(&mut Random::new(0));
+
+    for ((idx, param), random) in network.params_mut().iter_mut().enumerate().zip(random_values(usize::MAX)) {
+        let scale = policy_quantisation_scale(idx);
+        *param = (random * 0.5 * scale).round() / scale;
+    }
+
+    let bytes = network_bytes(&network).unwrap();
+    assert_eq!(bytes.len(), NetworkHeader::SIZE + std::mem::size_of_val(&engine::PolicyNetwork));
+
+    let header = NetworkHeader::from_bytes(&bytes).unwrap();
+    assert_eq!(header.architecture(), NetworkHeader::POLICY_ARCHITECTURE);
+    assert_eq!(header.payload_size(), bytes.len() - NetworkHeader::SIZE);
+
+    let path = write_network("jackal_test_trainer_policy.network", &bytes);
+    load_policy_network(&path).unwrap();
+
+    for fen in FENS {
+        let board = ChessBoard::from(&FEN::from(fen));
+        let inputs = policy_network().get_inputs(&board);
+        let mut cache = PolicyCache::default();
+
+        board.map_legal_moves(|mv| {
+            let expected = network.forward(&board, mv);
+            let result = policy_network().forward(&board, &inputs, mv, &mut cache);
+
+            assert!((result - expected).abs() <= 1e-4 * (expected.abs() + 1.0), "{fen} {mv:?}: {result} != {expected}");
+        });
+    }
+
+    load_policy_network("").unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn value_export_refuses_clamped_values() {
+    let mut network = ValueNetwork::new(&mut Random::new(0));
+    network.params_mut()[0] = 200.0;
+
+    let err = network_bytes(&network).unwrap_err();
+    assert!(err.contains("1 values outside"));
+}
@@ -1,5 +1,6 @@
 mod color_config;
 mod color_utils;
+mod random;
 mod terminal_utils;
 
 pub use color_utils::heat_color;
@@ -9,6 +10,7 @@ pub use color_utils::CustomColor;
 pub use color_utils::PieceColors;
 pub use color_utils::Theme;
 pub use color_config::{WIN_COLOR, DRAW_COLOR, LOSE_COLOR};
+pub use random::Random;
 pub use terminal_utils::bytes_to_string;
 pub use terminal_utils::clear_terminal_screen;
 pub use terminal_utils::create_loading_bar;
@@ -0,0 +1,38 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//-----------------------------------------------
+// Small xorshift64* generator, good enough for datagen openings and trainer sampling.
+//-----------------------------------------------
+
+pub struct Random(u64);
+impl Random {
+    pub fn new(salt: u64) -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0);
+
+        let mut result = Self((time ^ salt.wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1);
+
+        for _ in 0..8 {
+            result.next_u64();
+        }
+
+        result
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn range(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}